@@ -1,6 +1,12 @@
 
 pub mod intersect;
 pub mod scatter;
+pub mod evaluate;
+pub mod participate;
+pub mod illuminate;
 
 pub use intersect::{ Intersect, IntersectResult };
 pub use scatter::{ Scatter, ScatterResult };
+pub use evaluate::Evaluate;
+pub use participate::{ Participate, MediumSample };
+pub use illuminate::{ Illuminate, LightSample };
@@ -0,0 +1,9 @@
+use enum_dispatch::enum_dispatch;
+
+use crate::Vec3;
+
+
+#[enum_dispatch(Texture)]
+pub trait Evaluate {
+    fn value(&self, u: f64, v: f64, point: Vec3) -> Vec3;
+}
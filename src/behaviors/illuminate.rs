@@ -0,0 +1,24 @@
+use enum_dispatch::enum_dispatch;
+
+use crate::Vec3;
+use crate::lights::LightBounds;
+
+
+#[enum_dispatch(Light)]
+pub trait Illuminate {
+    // light arriving at a point, None if the point is not lit at all
+    fn sample(&self, point: Vec3) -> Option<LightSample>;
+    // None for lights infinitely far away, they can't be placed in space
+    fn bounds(&self) -> Option<LightBounds>;
+}
+
+
+#[derive(Debug, Clone)]
+pub struct LightSample {
+    // unit vector from the lit point towards the light
+    pub direction: Vec3,
+    // distance to the light, infinite for directional lights
+    pub distance: f64,
+    // incoming radiance, falloff already applied
+    pub radiance: Vec3,
+}
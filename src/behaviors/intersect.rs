@@ -1,14 +1,31 @@
 use std::fmt;
+use std::sync::Arc;
 
 use crate::Vec3;
 use crate::Ray;
 use crate::objects::{ Aabb, Object };
+use crate::media::Medium;
 
 use enum_dispatch::enum_dispatch;
 
 #[enum_dispatch(Object)]
 pub trait Intersect {
     fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64) -> Option<IntersectResult>;
+
+    // every hit along the ray from near to far, front faces entering the
+    // object and back faces leaving it. found one nearest hit after
+    // another unless an object knows all of them at once
+    fn intersect_all(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<IntersectResult> {
+        let mut hits = vec![];
+        let mut t = t_min;
+        while let Some(hit) = self.intersect(ray, t, t_max) {
+            // step past the hit so it isn't found again
+            t = hit.t + 1e-7 * (1.0 + hit.t.abs());
+            hits.push(hit);
+        }
+        hits
+    }
+
     fn bbox(&self) -> Aabb;
     fn divide(&self) -> Option<Vec<Object>>;
     fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result;
@@ -29,6 +46,16 @@ pub struct IntersectResult {
     pub normal: Vec3,
     pub front_face: bool,
     pub material: usize,
+    pub u: f64,
+    pub v: f64,
+    // surface derivatives dp/du and dp/dv, zero if the object has none
+    pub tangent: Vec3,
+    pub bitangent: Vec3,
+    // set when the hit is on the boundary of a participating medium
+    pub medium: Option<Arc<Medium>>,
+    // the primitive is registered as an area light when its material
+    // emits, light sampling already accounts for its emission
+    pub sampled: bool,
 }
 
 impl IntersectResult {
@@ -39,6 +66,37 @@ impl IntersectResult {
             true => outward_normal,
             false => -outward_normal,
         };
-        IntersectResult { t, point, normal, front_face, material: mat }
+        IntersectResult {
+            t, point, normal, front_face,
+            material: mat,
+            u: 0.0,
+            v: 0.0,
+            tangent: Vec3::zero(),
+            bitangent: Vec3::zero(),
+            medium: None,
+            sampled: false,
+        }
+    }
+
+    pub fn with_uv(mut self, u: f64, v: f64) -> Self {
+        self.u = u;
+        self.v = v;
+        self
+    }
+
+    pub fn with_tangents(mut self, tangent: Vec3, bitangent: Vec3) -> Self {
+        self.tangent = tangent;
+        self.bitangent = bitangent;
+        self
+    }
+
+    pub fn sampled(mut self) -> Self {
+        self.sampled = true;
+        self
+    }
+
+    pub fn with_medium(mut self, medium: Arc<Medium>) -> Self {
+        self.medium = Some(medium);
+        self
     }
 }
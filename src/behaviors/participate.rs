@@ -0,0 +1,24 @@
+use enum_dispatch::enum_dispatch;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::media::Phase;
+
+
+#[enum_dispatch(Medium)]
+pub trait Participate {
+    // samples the next interaction along the ray before t_max
+    fn sample(&self, ray: &Ray, t_max: f64) -> MediumSample;
+    // fraction of light surviving from the ray origin to t_max
+    fn transmittance(&self, ray: &Ray, t_max: f64) -> Vec3;
+    fn phase(&self) -> Phase;
+}
+
+
+#[derive(Debug, Clone)]
+pub struct MediumSample {
+    // ray parameter of a scattering event, None if the ray passed through
+    pub t: Option<f64>,
+    // throughput of the sampled path segment divided by its pdf
+    pub weight: Vec3,
+}
@@ -3,12 +3,21 @@ use enum_dispatch::enum_dispatch;
 use crate::Vec3;
 use crate::Ray;
 use crate::behaviors::IntersectResult;
+use crate::textures::NormalMap;
+use crate::media::Medium;
 
 
 #[enum_dispatch(Material)]
 pub trait Scatter {
     fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult>;
-    fn emit(&self) -> Vec3 { Vec3::zero() }
+    fn emit(&self, _: &IntersectResult) -> Vec3 { Vec3::zero() }
+    // bsdf times cosine for light arriving from the unit direction wi,
+    // zero for perfectly specular materials which lights can't be sampled for
+    fn eval(&self, _: &Ray, _: &IntersectResult, _: Vec3) -> Vec3 { Vec3::zero() }
+    fn normal_map(&self) -> Option<&NormalMap> { None }
+    // medium filling the inside of closed objects with this material, rays
+    // scattered through the surface enter or leave it
+    fn interior(&self) -> Option<&Medium> { None }
 }
 
 
@@ -16,6 +25,9 @@ pub trait Scatter {
 pub struct ScatterResult {
     pub ray: Ray,
     pub attenuation: Vec3,
+    // sampled from a lobe eval leaves out (smooth or refracted), lights hit
+    // by the ray weren't reached by light sampling and count in full
+    pub specular: bool,
 }
 
 // impl ScatterResult {
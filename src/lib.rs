@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use rayon::prelude::*;
 
 use rand::Rng;
@@ -15,13 +17,18 @@ mod utils;
 mod behaviors;
 mod objects;
 mod materials;
+mod textures;
+mod media;
+mod lights;
 mod scenes;
 
 
 use crate::utils::{ Color, Vec3, Ray, Matrix4, pretty_print_int };
-use crate::behaviors::{ Intersect, Scatter };
-use crate::objects::{ Object, BvhNode };
+use crate::behaviors::{ Scatter, Participate, Illuminate, LightSample };
+use crate::objects::{ Tlas, Sealed };
 use crate::materials::Material;
+use crate::media::Medium;
+use crate::lights::{ AreaLight, LightBvh, LightGroups };
 
 const ASPECT_RATIO: f64 = 16.0 / 9.0;
 const WIDTH: usize = 800;
@@ -29,38 +36,70 @@ const HEIGHT: usize = (WIDTH as f64 / ASPECT_RATIO) as usize;
 const SAMPLES_PER_PIXEL: usize = 100;
 const RAY_DEPTH: usize = 150;
 const MULTICORE: bool = false;
+// also write one image per light group next to the full image
+const LIGHT_PASSES: bool = false;
 const BIAS: f64 = 0.005;
 
 
 
 pub fn raytrace() {
 
-    // let (camera, materials, world) = scenes::spheres(ASPECT_RATIO, 0.3);
-    // let (camera, materials, world) = scenes::meshtest(ASPECT_RATIO, 0.2);
-    let (camera, materials, world) = scenes::teapot_with_lights(ASPECT_RATIO, 0.15);
-    // let (camera, materials, world) = scenes::cubes(ASPECT_RATIO, 0.3);
-    // let (camera, materials, world) = scenes::cornell_box(ASPECT_RATIO, 0.0);
-    // let (camera, materials, world) = scenes::airplane(ASPECT_RATIO, 0.0);
-    // let (camera, materials, world) = scenes::temple(ASPECT_RATIO, 6.0);
+    // let (camera, materials, world, mut lights) = scenes::spheres(ASPECT_RATIO, 0.3);
+    // let (camera, materials, world, mut lights) = scenes::meshtest(ASPECT_RATIO, 0.2);
+    let (camera, materials, world, mut lights) = scenes::teapot_with_lights(ASPECT_RATIO, 0.15);
+    // let (camera, materials, world, mut lights) = scenes::cubes(ASPECT_RATIO, 0.3);
+    // let (camera, materials, world, mut lights) = scenes::cornell_box(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::airplane(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::temple(ASPECT_RATIO, 6.0);
+    // let (camera, materials, world, mut lights) = scenes::globes(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::procedural(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::bumpy_teapot(ASPECT_RATIO, 0.15);
+    // let (camera, materials, world, mut lights) = scenes::metals(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::glass(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::glass_teapot(ASPECT_RATIO, 0.15);
+    // let (camera, materials, world, mut lights) = scenes::material_balls(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::billiards(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::mixed(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::volumes(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::clouds(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::subsurface(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::stage(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::softboxes(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::ies_wall(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::teapot_forest(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::ellipsoids(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::primitives(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::csg(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::sdf(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::fur(ASPECT_RATIO, 0.0);
+    // let (camera, materials, world, mut lights) = scenes::subdivision(ASPECT_RATIO, 0.0);
     // println!("{:#?}", &world);
 
-    let mut primitives: Vec<Object> = vec![];
-
-    for object in world.into_iter() {
-        if let Some(inner_objs) = object.divide() {
-            primitives.extend(inner_objs);
-        } else {
-            primitives.push(object);
-        }
-    }
-
-    let mut objects: Vec<Object> = vec![];
-    let mut nodes: Vec<BvhNode> = vec![];
-    let root = BvhNode::construct(primitives, &mut objects, &mut nodes);
-    // println!("{:?}", root);
-
-
-    let mut buffer: Vec<Color> = vec![Color::black() ; WIDTH * HEIGHT];
+    let mut sealed = Sealed::new();
+    let world = world
+        .into_iter()
+        .map(|mut object| { object.seal(&materials, None, &mut sealed); object })
+        .collect();
+    let tlas = Tlas::new(world);
+
+    let groups = LightGroups::new(&materials, &lights);
+    lights.extend(
+        tlas.objects.iter().flat_map(|object| AreaLight::from_object(object, &materials))
+    );
+    let lights = LightBvh::new(lights);
+    let scene = Scene {
+        tlas: &tlas,
+        materials: &materials,
+        fog: camera.medium.as_ref(),
+        lights: &lights,
+        groups: &groups,
+    };
+    // println!("{:?}", tlas.root);
+
+
+    // per pixel sum of the samples of every light group pass
+    let passes = groups.count();
+    let mut buffer: Vec<Vec<Vec3>> = vec![vec![Vec3::zero(); passes] ; WIDTH * HEIGHT];
 
     println!("Starting Render...");
     if !MULTICORE {
@@ -77,27 +116,24 @@ pub fn raytrace() {
                     HEIGHT - h as usize - 1, HEIGHT - 1
                 );
 
-                let color: Vec3 = (&mut rng)
+                *x = (&mut rng)
                     .sample_iter(rand::distributions::Standard)
                     .take(SAMPLES_PER_PIXEL)
                     .collect::<Vec<(f64, f64)>>()
                     .into_iter()
-                    .fold(Vec3::zero(), |c, (a, b)| {
+                    .fold(vec![Vec3::zero(); passes], |mut c, (a, b)| {
                         let u = (w + a) / WIDTH as f64;
                         let v = (h + b) / HEIGHT as f64;
 
                         unsafe { RAY_COUNT_PRIMARY += 1; }
 
                         let ray = camera.get_ray(u, v);
-                        let col = ray_color(
-                            root, &objects, &materials, &nodes,
-                            ray, RAY_DEPTH
+                        ray_color(
+                            &scene, camera.medium.as_ref(), ray, Path::camera(), &mut c, RAY_DEPTH
                         );
 
-                        c + col
+                        c
                     });
-
-                *x = Color::to_u8(color, SAMPLES_PER_PIXEL);
             });
     } else {
 
@@ -114,12 +150,12 @@ pub fn raytrace() {
                 //     HEIGHT - h as usize - 1, HEIGHT - 1
                 // );
 
-                let color: Vec3 = (&mut rng)
+                *x = (&mut rng)
                     .sample_iter(rand::distributions::Standard)
                     .take(SAMPLES_PER_PIXEL)
                     .collect::<Vec<(f64, f64)>>()
                     .into_par_iter()
-                    .fold(|| Vec3::zero(), |c, (a, b)| {
+                    .fold(|| vec![Vec3::zero(); passes], |mut c, (a, b)| {
 
                         let u = (w + a) / WIDTH as f64;
                         let v = (h + b) / HEIGHT as f64;
@@ -127,22 +163,36 @@ pub fn raytrace() {
                         unsafe { RAY_COUNT_PRIMARY += 1; }
 
                         let ray = camera.get_ray(u, v);
-                        let col = ray_color(
-                            root, &objects, &materials, &nodes,
-                            ray, RAY_DEPTH
+                        ray_color(
+                            &scene, camera.medium.as_ref(), ray, Path::camera(), &mut c, RAY_DEPTH
                         );
 
-                        c + col
+                        c
                     })
-                    .reduce(|| Vec3::zero(), |acc, cur| acc + cur);
-
-                *x = Color::to_u8(color, SAMPLES_PER_PIXEL);
+                    .reduce(|| vec![Vec3::zero(); passes], |mut acc, cur| {
+                        acc.iter_mut().zip(cur).for_each(|(a, c)| *a += c);
+                        acc
+                    });
             });
     }
     println!();
 
     println!("Exporting image");
-    utils::image_export("image.ppm", &buffer, WIDTH, HEIGHT);
+    let image: Vec<Color> = buffer
+        .iter()
+        .map(|x| Color::to_u8(x.iter().fold(Vec3::zero(), |a, c| a + *c), SAMPLES_PER_PIXEL))
+        .collect();
+    utils::image_export("image.ppm", &image, WIDTH, HEIGHT);
+
+    if LIGHT_PASSES {
+        for (pass, name) in groups.names.iter().enumerate() {
+            let image: Vec<Color> = buffer
+                .iter()
+                .map(|x| Color::to_u8(x[pass], SAMPLES_PER_PIXEL))
+                .collect();
+            utils::image_export(&format!("image_{}.ppm", name), &image, WIDTH, HEIGHT);
+        }
+    }
     println!("\n Image exported!");
 
     unsafe {
@@ -159,37 +209,125 @@ pub fn raytrace() {
 
 static T_MIN: f64 = 0.0001;
 static T_MAX: f64 = f64::INFINITY;
+// medium boundaries a shadow ray may pass before it counts as blocked
+const SHADOW_CROSSINGS: usize = 16;
+
+
+// everything rays are traced against, shared by all ray_color calls
+struct Scene<'a> {
+    tlas: &'a Tlas,
+    materials: &'a [Material],
+    // medium of the open scene outside of any medium boundary
+    fog: Option<&'a Medium>,
+    lights: &'a LightBvh,
+    groups: &'a LightGroups,
+}
+
+
+// state carried down a path, light found along it is weighted by the
+// throughput. `specular` is set for camera rays and rays scattered by lobes
+// light sampling can't reach, only those count the emission of sampled lights
+#[derive(Debug, Clone, Copy)]
+struct Path {
+    throughput: Vec3,
+    specular: bool,
+}
 
 
+impl Path {
+    fn camera() -> Self {
+        Self { throughput: Vec3::new(1.0, 1.0, 1.0), specular: true }
+    }
+}
+
+
+// adds the light carried back along `ray` to the light group passes
 fn ray_color(
-    root: usize, objects: &[Object], materials: &[Material],
-    nodes: &[BvhNode], ray: Ray, depth: usize
-) -> Vec3 {
+    scene: &Scene, medium: Option<&Medium>, ray: Ray, path: Path,
+    passes: &mut [Vec3], depth: usize,
+) {
 
-    if depth <= 0 { return Vec3::zero() }
+    if depth <= 0 { return }
 
-    debug_assert!(root < nodes.len());
-    let node = unsafe { nodes.get_unchecked(root) };
+    let Scene { tlas, materials, fog, groups, .. } = *scene;
 
-    if let Some(result) = node.intersect(&ray, T_MIN, T_MAX, objects, nodes) {
-        let material = &materials[result.material];
-        let emitted = material.emit();
+    let hit = tlas.intersect(&ray, T_MIN, T_MAX);
 
-        match material.scatter(&ray, result) {
-            Some(r) => {
-                let color = ray_color(
-                    root, objects, materials, nodes, r.ray, depth - 1
-                );
-                return emitted + r.attenuation * color
-            },
-            None => {
-                return emitted
-            }
+    // the medium the ray travels through either scatters it on the way
+    // or attenuates whatever it hits
+    let mut throughput = path.throughput;
+    if let Some(current) = medium {
+        let t_max = hit.as_ref().map_or(T_MAX, |result| result.t);
+        let sample = current.sample(&ray, t_max);
+        throughput *= sample.weight;
+
+        if let Some(t) = sample.t {
+            let phase = current.phase();
+            direct_light(scene, medium, ray.at(t), throughput, passes, |wi| {
+                Vec3::new(1.0, 1.0, 1.0) * phase.eval(ray.direction(), wi)
+            });
+
+            let scattered = Ray::new(ray.at(t), phase.sample(ray.direction()));
+            let path = Path { throughput, specular: false };
+            return ray_color(scene, medium, scattered, path, passes, depth - 1)
+        }
+    }
+
+    if let Some(mut result) = hit {
+
+        // invisible medium boundary, continue straight on in the medium
+        // entered or back out into the fog
+        if let Some(boundary) = result.medium.take() {
+            let next = match result.front_face {
+                true => Some(&*boundary),
+                false => fog,
+            };
+            let through = Ray::new(
+                result.point - (crate::BIAS * result.normal), ray.direction()
+            );
+            let path = Path { throughput, ..path };
+            return ray_color(scene, next, through, path, passes, depth - 1)
+        }
+
+        // emissive primitives are area lights, already added by light
+        // sampling at the previous hit unless it scattered specularly.
+        // shapes that aren't sampled always count their emission
+        let emitted = match &materials[result.material] {
+            Material::DiffuseLight(_) if !path.specular && result.sampled => Vec3::zero(),
+            m => m.emitted(materials, &result, 0),
+        };
+        passes[groups.of_material(result.material)] += throughput * emitted;
+
+        let material = materials[result.material].resolve(materials, &result);
+        if let Some(map) = material.normal_map() {
+            map.apply(&ray, &mut result);
+        }
+
+        let (normal, front_face) = (result.normal, result.front_face);
+
+        let origin = result.point + (crate::BIAS * normal);
+        direct_light(scene, medium, origin, throughput, passes, |wi| {
+            material.eval(&ray, &result, wi)
+        });
+
+        if let Some(r) = material.scatter(&ray, result) {
+            // scattering through the surface of a material with an
+            // interior enters or leaves its medium
+            let through = r.ray.direction().dot(normal) < 0.0;
+            let next = match material.interior() {
+                Some(interior) if through && front_face => Some(interior),
+                Some(_) if through => fog,
+                _ => medium,
+            };
+
+            let path = Path { throughput: throughput * r.attenuation, specular: r.specular };
+            ray_color(scene, next, r.ray, path, passes, depth - 1);
         }
+        return
     }
 
     // Vec3::new(1.0, 1.0, 1.0)
-    Vec3::new(0.001, 0.001, 0.001)
+    passes[0] += throughput * Vec3::new(0.001, 0.001, 0.001)
     // Vec3::zero()
     // let unit_direction = ray.direction().unit();
     // let t = 0.5 * (unit_direction.y + 1.0);
@@ -208,3 +346,71 @@ fn ray_color(
     // // ) * 0.001
     // )
 }
+
+
+// light arriving at `point` straight from one light of the scene, picked by
+// the light bvh, weighted by `f` for the direction towards it and added to
+// the pass of the light. scattered rays that hit an area light later don't
+// count its emission again
+fn direct_light(
+    scene: &Scene, medium: Option<&Medium>, point: Vec3, throughput: Vec3,
+    passes: &mut [Vec3], f: impl Fn(Vec3) -> Vec3,
+) {
+    let (index, light, pmf) = match scene.lights.pick(point) {
+        Some(picked) => picked,
+        None => return,
+    };
+    let sample = match light.sample(point) {
+        Some(sample) => sample,
+        None => return,
+    };
+
+    let weight = f(sample.direction);
+    if weight == Vec3::zero() { return }
+
+    let direct = weight * sample.radiance * shadow(scene, medium, point, &sample) / pmf;
+    passes[scene.groups.of_light(index, light)] += throughput * direct;
+}
+
+
+// share of the light that makes it from the light to `point`. any surface on
+// the way blocks it, medium boundaries are crossed and attenuate it
+fn shadow(
+    scene: &Scene, medium: Option<&Medium>, point: Vec3, sample: &LightSample
+) -> Vec3 {
+
+    let Scene { tlas, fog, .. } = *scene;
+
+    let mut transmittance = Vec3::new(1.0, 1.0, 1.0);
+    // medium boundary last entered, outside of it the ray is in `outside`
+    let mut entered: Option<Arc<Medium>> = None;
+    let mut outside = medium;
+    let mut ray = Ray::new(point, sample.direction);
+    let mut remaining = sample.distance;
+
+    for _ in 0..SHADOW_CROSSINGS {
+        let hit = tlas.intersect(&ray, T_MIN, remaining);
+        if let Some(medium) = entered.as_deref().or(outside) {
+            let t = hit.as_ref().map_or(remaining, |result| result.t);
+            transmittance *= medium.transmittance(&ray, t);
+        }
+
+        let mut result = match hit {
+            Some(result) => result,
+            None => return transmittance,
+        };
+        let boundary = match result.medium.take() {
+            Some(boundary) => boundary,
+            None => return Vec3::zero(),
+        };
+
+        match result.front_face {
+            true => entered = Some(boundary),
+            false => (entered, outside) = (None, fog),
+        };
+        remaining -= result.t;
+        ray = Ray::new(result.point - (crate::BIAS * result.normal), sample.direction);
+    }
+
+    Vec3::zero()
+}
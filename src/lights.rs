@@ -0,0 +1,77 @@
+use enum_dispatch::enum_dispatch;
+
+
+pub mod point_light;
+pub mod spot_light;
+pub mod directional_light;
+pub mod area_light;
+pub mod light_bvh;
+pub mod light_groups;
+
+pub use point_light::{ PointLight, Falloff, Profile };
+pub use spot_light::SpotLight;
+pub use directional_light::DirectionalLight;
+pub use area_light::AreaLight;
+pub use light_bvh::{ LightBvh, LightBounds };
+pub use light_groups::LightGroups;
+
+
+use std::sync::Arc;
+
+use crate::Matrix4;
+use crate::utils::IesProfile;
+
+// for enum dispatch
+use crate::Vec3;
+use crate::behaviors::{ Illuminate, LightSample };
+
+
+// lights reached through shadow rays. delta lights come from the scene,
+// area lights are collected from the emissive primitives of the world.
+// the variants are named after their structs for enum dispatch
+#[allow(clippy::enum_variant_names)]
+#[enum_dispatch]
+#[derive(Debug, Clone)]
+pub enum Light {
+    PointLight,
+    SpotLight,
+    DirectionalLight,
+    AreaLight,
+}
+
+
+impl Light {
+
+    // shapes a point or spot light by a measured ies profile. orientation
+    // rotates luminaire space, with the light pointing down -y, into the world
+    pub fn with_profile(mut self, ies: Arc<IesProfile>, orientation: Matrix4) -> Self {
+        let profile = Some(Profile::new(ies, orientation));
+        match &mut self {
+            Light::PointLight(l) => l.profile = profile,
+            Light::SpotLight(l) => l.profile = profile,
+            _ => panic!("{:?} does not support ies profiles", self),
+        }
+        self
+    }
+
+    // puts the light into a named light group pass
+    pub fn in_group(mut self, name: &str) -> Self {
+        let group = Some(String::from(name));
+        match &mut self {
+            Light::PointLight(l) => l.group = group,
+            Light::SpotLight(l) => l.group = group,
+            Light::DirectionalLight(l) => l.group = group,
+            Light::AreaLight(_) => panic!("area lights are grouped by their material"),
+        }
+        self
+    }
+
+    pub fn group(&self) -> Option<&String> {
+        match self {
+            Light::PointLight(l) => l.group.as_ref(),
+            Light::SpotLight(l) => l.group.as_ref(),
+            Light::DirectionalLight(l) => l.group.as_ref(),
+            Light::AreaLight(_) => None,
+        }
+    }
+}
@@ -0,0 +1,410 @@
+use std::f64::consts::PI;
+
+use crate::{ Vec3, Matrix4 };
+use crate::behaviors::{ Illuminate, LightSample };
+use crate::behaviors::Intersect;
+use crate::objects::{ Object, Aabb, Transform, Quad, Disk, Cylinder, Cone, Torus };
+use crate::materials::Material;
+use crate::utils::color::luminance;
+
+use crate::lights::{ Light, LightBounds };
+
+use rand::Rng;
+
+
+#[derive(Debug, Clone)]
+enum Shape {
+    Triangle { v0: Vec3, e1: Vec3, e2: Vec3 },
+    Sphere { center: Vec3, radius: f64 },
+    // infinite, lights everything on its front side
+    Plane { point: Vec3, normal: Vec3 },
+    Quad(Quad),
+    Disk(Disk),
+    Cylinder(Cylinder),
+    Cone(Cone),
+    Torus(Torus),
+    // a shape under a transform, `det` is its volume scale. spheres
+    // stretched this way are ellipsoids
+    Stretched { shape: Box<Shape>, transform: Box<Transform>, det: f64 },
+}
+
+
+impl Shape {
+
+    // point and normal picked uniformly by area, with the area they were
+    // picked from. planes have no finite area to pick from
+    fn surface(&self) -> Option<(Vec3, Vec3, f64)> {
+        let mut rng = rand::thread_rng();
+
+        match self {
+            Shape::Triangle { v0, e1, e2 } => {
+                let root = rng.gen::<f64>().sqrt();
+                let (b1, b2) = (1.0 - root, rng.gen::<f64>() * root);
+
+                let cross = e1.cross(*e2);
+                let area = 0.5 * cross.len();
+                if area <= 0.0 { return None }
+
+                Some((*v0 + b1 * *e1 + b2 * *e2, cross.unit(), area))
+            },
+            Shape::Sphere { center, radius } => {
+                let normal = Vec3::random_in_unit_sphere().unit();
+                Some((*center + *radius * normal, normal, 4.0 * PI * radius * radius))
+            },
+            Shape::Plane { .. } => None,
+            Shape::Quad(quad) => { let (p, n) = quad.sample(); Some((p, n, quad.area())) },
+            Shape::Disk(disk) => { let (p, n) = disk.sample(); Some((p, n, disk.area())) },
+            Shape::Cylinder(cylinder) => {
+                let (p, n) = cylinder.sample();
+                Some((p, n, cylinder.area()))
+            },
+            Shape::Cone(cone) => { let (p, n) = cone.sample(); Some((p, n, cone.area())) },
+            Shape::Torus(torus) => { let (p, n) = torus.sample(); Some((p, n, torus.area())) },
+            // the area element is stretched by the volume scale times the
+            // length of the transformed normal
+            Shape::Stretched { shape, transform, det } => {
+                let (p, n, area) = shape.surface()?;
+                let normal = transform.normal.transform_vector(n);
+                Some((transform.matrix * p, normal.unit(), area * det * normal.len()))
+            },
+        }
+    }
+
+    fn area(&self) -> f64 {
+        match self {
+            Shape::Triangle { e1, e2, .. } => 0.5 * e1.cross(*e2).len(),
+            Shape::Sphere { radius, .. } => 4.0 * PI * radius * radius,
+            Shape::Plane { .. } => f64::INFINITY,
+            Shape::Quad(quad) => quad.area(),
+            Shape::Disk(disk) => disk.area(),
+            Shape::Cylinder(cylinder) => cylinder.area(),
+            Shape::Cone(cone) => cone.area(),
+            Shape::Torus(torus) => torus.area(),
+            // the area scaled like a uniform stretch of the same volume is
+            // close enough for picking lights
+            Shape::Stretched { shape, det, .. } => shape.area() * det.powf(2.0 / 3.0),
+        }
+    }
+
+    fn bbox(&self) -> Aabb {
+        match self {
+            Shape::Triangle { v0, e1, e2 } => {
+                let (v1, v2) = (*v0 + *e1, *v0 + *e2);
+                let lower = Vec3::new(
+                    v0.x.min(v1.x).min(v2.x), v0.y.min(v1.y).min(v2.y), v0.z.min(v1.z).min(v2.z)
+                );
+                let upper = Vec3::new(
+                    v0.x.max(v1.x).max(v2.x), v0.y.max(v1.y).max(v2.y), v0.z.max(v1.z).max(v2.z)
+                );
+                Aabb { lower, upper }
+            },
+            Shape::Sphere { center, radius } => {
+                let r = Vec3::new(*radius, *radius, *radius);
+                Aabb { lower: *center - r, upper: *center + r }
+            },
+            Shape::Plane { .. } => Aabb { lower: Vec3::neg_inf(), upper: Vec3::inf() },
+            Shape::Quad(quad) => quad.bbox(),
+            Shape::Disk(disk) => disk.bbox(),
+            Shape::Cylinder(cylinder) => cylinder.bbox(),
+            Shape::Cone(cone) => cone.bbox(),
+            Shape::Torus(torus) => torus.bbox(),
+            Shape::Stretched { shape, transform, .. } => transform.bbox(&shape.bbox()),
+        }
+    }
+
+    // the normal shared by every point of flat shapes
+    fn axis(&self) -> Option<Vec3> {
+        match self {
+            Shape::Triangle { e1, e2, .. } => Some(e1.cross(*e2).unit()),
+            Shape::Plane { normal, .. } => Some(*normal),
+            Shape::Quad(quad) => Some(quad.u.cross(quad.v).unit()),
+            Shape::Disk(disk) => Some(disk.normal),
+            Shape::Stretched { shape, transform, .. } => shape
+                .axis()
+                .map(|axis| transform.normal.transform_vector(axis).unit()),
+            _ => None,
+        }
+    }
+}
+
+
+// emissive primitive of the world, sampled so shadow rays can find it.
+// spheres are sampled by the cone of directions they cover as seen from
+// the lit point, planes by the half of all directions facing them and
+// every other shape uniformly by area
+#[derive(Debug, Clone)]
+pub struct AreaLight {
+    shape: Shape,
+    radiance: Vec3,
+    two_sided: bool,
+    pub material: usize,
+}
+
+
+impl AreaLight {
+
+    // one light per emissive primitive, none for primitives without a
+    // diffuse light material. instances give one light for every emissive
+    // primitive of their prototype, moved into place. these are the
+    // primitives whose hits are marked as sampled
+    pub fn from_object(object: &Object, materials: &[Material]) -> Vec<Light> {
+        AreaLight::lights(object, None, materials)
+            .into_iter()
+            .map(Light::from)
+            .collect()
+    }
+
+    // `material` overrides the materials of the primitives, like it does
+    // for hits on an instance
+    fn lights(object: &Object, material: Option<usize>, materials: &[Material]) -> Vec<AreaLight> {
+        let (shape, own) = match object {
+            Object::Triangle(triangle) => {
+                let [v0, v1, v2] = triangle.vertices();
+                let shape = Shape::Triangle { v0, e1: v1 - v0, e2: v2 - v0 };
+                (shape, triangle.mesh.material)
+            },
+            Object::Sphere(sphere) => {
+                let shape = Shape::Sphere { center: sphere.center, radius: sphere.radius };
+                (shape, sphere.material)
+            },
+            // the outer override wins, it is applied to the hit last
+            Object::Instance(instance) => return instance.prototype.objects
+                .iter()
+                .flat_map(|object| {
+                    AreaLight::lights(object, material.or(instance.material), materials)
+                })
+                .map(|light| light.placed(&instance.transform))
+                .collect(),
+            Object::Transformed(transformed) => {
+                return AreaLight::lights(&transformed.object, material, materials)
+                    .into_iter()
+                    .map(|light| light.placed(&transformed.transform))
+                    .collect()
+            },
+            Object::Plane(plane) => {
+                (Shape::Plane { point: plane.point, normal: plane.normal }, plane.material)
+            },
+            Object::Quad(quad) => (Shape::Quad(quad.clone()), quad.material),
+            Object::Disk(disk) => (Shape::Disk(disk.clone()), disk.material),
+            Object::Cylinder(cylinder) => (Shape::Cylinder(cylinder.clone()), cylinder.material),
+            Object::Cone(cone) => (Shape::Cone(cone.clone()), cone.material),
+            Object::Torus(torus) => (Shape::Torus(torus.clone()), torus.material),
+            _ => return vec![],
+        };
+
+        AreaLight::emissive(shape, material.unwrap_or(own), materials).into_iter().collect()
+    }
+
+    fn emissive(shape: Shape, material: usize, materials: &[Material]) -> Option<AreaLight> {
+        match &materials[material] {
+            Material::DiffuseLight(light) => Some(Self {
+                shape,
+                radiance: light.radiance(),
+                two_sided: light.two_sided,
+                material,
+            }),
+            _ => None,
+        }
+    }
+
+    // the light moved by a transform. spheres stay spheres under rotation,
+    // translation and uniform scale, anything else stretches them into
+    // ellipsoids. triangles and planes are moved as they are, other shapes
+    // keep their own frame and are sampled through the transform
+    fn placed(self, transform: &Transform) -> AreaLight {
+        if transform.identity { return self }
+
+        let m = transform.matrix;
+        let stretched = |shape: Shape, matrix: Matrix4| Shape::Stretched {
+            shape: Box::new(shape),
+            transform: Box::new(Transform::new(matrix)),
+            det: Matrix4::determinant(matrix.matrix, 4).abs(),
+        };
+
+        let shape = match self.shape {
+            Shape::Triangle { v0, e1, e2 } => Shape::Triangle {
+                v0: m * v0, e1: m.transform_vector(e1), e2: m.transform_vector(e2),
+            },
+            Shape::Sphere { center, radius } => {
+                let axes = [
+                    m.transform_vector(Vec3::new(1.0, 0.0, 0.0)),
+                    m.transform_vector(Vec3::new(0.0, 1.0, 0.0)),
+                    m.transform_vector(Vec3::new(0.0, 0.0, 1.0)),
+                ];
+                let scale = axes[0].len();
+                let uniform = axes.iter().all(|a| (a.len() - scale).abs() < 1e-6 * scale)
+                    && axes[0].dot(axes[1]).abs() < 1e-6 * scale * scale
+                    && axes[0].dot(axes[2]).abs() < 1e-6 * scale * scale
+                    && axes[1].dot(axes[2]).abs() < 1e-6 * scale * scale;
+                match uniform {
+                    true => Shape::Sphere { center: m * center, radius: radius * scale },
+                    false => stretched(Shape::Sphere { center, radius }, m),
+                }
+            },
+            Shape::Plane { point, normal } => Shape::Plane {
+                point: m * point,
+                normal: transform.normal.transform_vector(normal).unit(),
+            },
+            Shape::Stretched { shape, transform: inner, .. } => {
+                stretched(*shape, m * inner.matrix)
+            },
+            shape => stretched(shape, m),
+        };
+
+        Self { shape, ..self }
+    }
+
+    // converts a point picked uniformly on a surface of `area` to light
+    // arriving at `point`, the area density turns into solid angle by
+    // distance squared over the cosine at the light
+    fn incoming(
+        &self, point: Vec3, on_light: Vec3, normal: Vec3, area: f64
+    ) -> Option<LightSample> {
+        let to_light = on_light - point;
+        let distance = to_light.len();
+        if distance <= crate::BIAS { return None }
+        let direction = to_light / distance;
+
+        let cos = -direction.dot(normal);
+        if cos <= 0.0 && !self.two_sided { return None }
+        let cos = cos.abs();
+        if cos <= 0.0 { return None }
+
+        Some(LightSample {
+            direction,
+            // stop the shadow ray short of the light itself
+            distance: distance - crate::BIAS,
+            radiance: self.radiance * (cos * area / (distance * distance)),
+        })
+    }
+}
+
+
+impl Illuminate for AreaLight {
+
+    fn sample(&self, point: Vec3) -> Option<LightSample> {
+        let mut rng = rand::thread_rng();
+
+        match self.shape {
+            Shape::Sphere { center, radius } => {
+                let to_center = center - point;
+                let distance = to_center.len();
+
+                // from inside only the inner surface is seen, sample it by area
+                if distance <= radius {
+                    let (on_light, normal, area) = self.shape.surface()?;
+                    return self.incoming(point, on_light, normal, area)
+                }
+
+                let sin2_max = (radius * radius) / (distance * distance);
+                let cos_max = (1.0 - sin2_max).max(0.0).sqrt();
+                let solid_angle = 2.0 * PI * (1.0 - cos_max);
+                if solid_angle <= 0.0 { return None }
+
+                let cos = 1.0 - rng.gen::<f64>() * (1.0 - cos_max);
+                let sin = (1.0 - cos * cos).max(0.0).sqrt();
+                let phi = 2.0 * PI * rng.gen::<f64>();
+
+                let axis = to_center / distance;
+                let (t, b) = axis.orthonormal_basis();
+                let direction = (axis * cos) + (t * (sin * phi.cos())) + (b * (sin * phi.sin()));
+
+                // distance to the near side of the sphere along the direction
+                let d = distance * cos
+                    - (radius * radius - distance * distance * sin * sin).max(0.0).sqrt();
+
+                Some(LightSample {
+                    direction,
+                    distance: (d - crate::BIAS).max(0.0),
+                    radiance: self.radiance * solid_angle,
+                })
+            },
+
+            // every direction towards the plane hits it, pick one
+            // uniformly from that half of the sphere
+            Shape::Plane { point: on_plane, normal } => {
+                let height = (point - on_plane).dot(normal);
+                if height.abs() <= crate::BIAS { return None }
+                if height < 0.0 && !self.two_sided { return None }
+
+                let towards = -normal * height.signum();
+                let mut direction = Vec3::random_in_unit_sphere().unit();
+                if direction.dot(towards) < 0.0 { direction = -direction }
+                let cos = direction.dot(towards);
+                if cos <= 0.0 { return None }
+
+                Some(LightSample {
+                    direction,
+                    distance: height.abs() / cos - crate::BIAS,
+                    radiance: self.radiance * (2.0 * PI),
+                })
+            },
+
+            _ => {
+                let (on_light, normal, area) = self.shape.surface()?;
+                self.incoming(point, on_light, normal, area)
+            },
+        }
+    }
+
+    fn bounds(&self) -> Option<LightBounds> {
+        // planes light the whole scene like a directional light
+        if let Shape::Plane { .. } = self.shape { return None }
+
+        // a diffuse emitter sends pi times its radiance per area, per side
+        let sides = if self.two_sided { 2.0 } else { 1.0 };
+        let power = luminance(self.radiance) * PI * self.shape.area() * sides;
+
+        // flat shapes face one way, the rest have normals in every direction
+        let (axis, cos_theta_o) = match self.shape.axis() {
+            Some(axis) => (axis, 1.0),
+            None => (Vec3::new(0.0, 1.0, 0.0), -1.0),
+        };
+
+        Some(LightBounds {
+            bbox: self.shape.bbox(),
+            power,
+            axis,
+            cos_theta_o,
+            cos_theta_e: 0.0,
+            two_sided: self.two_sided,
+        })
+    }
+
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Ray;
+    use crate::objects::{ Sphere, Csg, Instance, Prototype };
+    use crate::materials::{ Lambertian, DiffuseLight };
+    use crate::Color;
+
+    // emission is only left to light sampling for hits on primitives that
+    // became lights, an instance lit by its material override included
+    #[test]
+    fn sampled_hits_are_lights() {
+        let materials = vec![Lambertian::new(Color::rgb(128, 128, 128)), DiffuseLight::white(1.0)];
+        let sphere = |x: f64, material: usize| -> Object {
+            Sphere { center: Vec3::new(x, 0.0, 0.0), radius: 1.0, material }.into()
+        };
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+
+        let lamp = sphere(0.0, 1);
+        assert_eq!(AreaLight::from_object(&lamp, &materials).len(), 1);
+        assert!(lamp.intersect(&ray, 0.001, f64::INFINITY).unwrap().sampled);
+
+        let ball = Prototype::new(vec![sphere(0.0, 0)]);
+        let lit = Instance::with_material(&ball, Matrix4::identity(), 1);
+        assert_eq!(AreaLight::from_object(&lit, &materials).len(), 1);
+        let lamp = Prototype::new(vec![sphere(0.0, 1)]);
+        let unlit = Instance::with_material(&lamp, Matrix4::identity(), 0);
+        assert!(AreaLight::from_object(&unlit, &materials).is_empty());
+
+        let lens = Csg::intersection(sphere(-0.5, 1), sphere(0.5, 1));
+        assert!(AreaLight::from_object(&lens, &materials).is_empty());
+        assert!(!lens.intersect(&ray, 0.001, f64::INFINITY).unwrap().sampled);
+    }
+}
@@ -0,0 +1,44 @@
+use crate::Vec3;
+use crate::Color;
+use crate::behaviors::{ Illuminate, LightSample };
+
+use crate::lights::{ Light, LightBounds };
+
+
+// infinitely far away light like the sun, parallel rays with no falloff
+#[derive(Debug, Clone)]
+pub struct DirectionalLight {
+    // direction the light travels in
+    direction: Vec3,
+    irradiance: Vec3,
+    pub group: Option<String>,
+}
+
+
+impl DirectionalLight {
+
+    pub fn new(direction: Vec3, color: Color, intensity: f64) -> Light {
+        Self {
+            direction: direction.unit(),
+            irradiance: color.normalize() * intensity,
+            group: None,
+        }.into()
+    }
+}
+
+
+impl Illuminate for DirectionalLight {
+
+    fn sample(&self, _: Vec3) -> Option<LightSample> {
+        Some(LightSample {
+            direction: -self.direction,
+            distance: f64::INFINITY,
+            radiance: self.irradiance,
+        })
+    }
+
+    fn bounds(&self) -> Option<LightBounds> {
+        None
+    }
+
+}
@@ -0,0 +1,350 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::behaviors::Illuminate;
+use crate::objects::Aabb;
+
+use crate::lights::Light;
+
+use rand::Rng;
+
+
+const N_BUCKETS: usize = 12;
+
+
+// spatial, power and emission direction bounds of one or more lights
+// (Conty Estevez, Kulla 2018). the emitting normals lie within theta_o of
+// `axis`, light leaves them at up to theta_e from the normal
+#[derive(Debug, Clone)]
+pub struct LightBounds {
+    pub bbox: Aabb,
+    // total emitted power, by luminance
+    pub power: f64,
+    pub axis: Vec3,
+    pub cos_theta_o: f64,
+    pub cos_theta_e: f64,
+    pub two_sided: bool,
+}
+
+
+impl LightBounds {
+
+    pub fn merge(self, other: Self) -> Self {
+        let (axis, cos_theta_o) = merge_cones(
+            (self.axis, self.cos_theta_o), (other.axis, other.cos_theta_o)
+        );
+        Self {
+            bbox: self.bbox.merge(other.bbox),
+            power: self.power + other.power,
+            axis,
+            cos_theta_o,
+            cos_theta_e: self.cos_theta_e.min(other.cos_theta_e),
+            two_sided: self.two_sided || other.two_sided,
+        }
+    }
+
+    // estimate of the light reaching `point`, conservative in direction:
+    // the emission cone is widened by the angle the bounds cover
+    pub fn importance(&self, point: Vec3) -> f64 {
+        if self.power <= 0.0 { return 0.0 }
+
+        let center = self.bbox.centroid();
+        let to_point = point - center;
+        let radius = self.bbox.diagonal().len() / 2.0;
+
+        // keep close points from blowing up, the bounds are not a point
+        let d2 = to_point.sq_len().max(radius).max(1e-8);
+
+        let mut cos_w = match to_point.sq_len() > 0.0 {
+            true => self.axis.dot(to_point.unit()),
+            false => 1.0,
+        };
+        if self.two_sided { cos_w = cos_w.abs() }
+
+        let cos_b = match to_point.sq_len() > radius * radius {
+            true => (1.0 - (radius * radius) / to_point.sq_len()).max(0.0).sqrt(),
+            false => -1.0,
+        };
+
+        // cosine of max(0, theta_w - theta_o - theta_b)
+        let cos_x = cos_sub_clamped(cos_w, self.cos_theta_o);
+        let cos = cos_sub_clamped(cos_x, cos_b);
+        if cos <= self.cos_theta_e { return 0.0 }
+
+        self.power * cos / d2
+    }
+
+    // solid angle measure of the emission directions (M_omega in the paper)
+    fn orientation(&self) -> f64 {
+        let theta_o = self.cos_theta_o.clamp(-1.0, 1.0).acos();
+        let theta_e = self.cos_theta_e.clamp(-1.0, 1.0).acos();
+        let theta_w = (theta_o + theta_e).min(PI);
+        let (sin_o, cos_o) = theta_o.sin_cos();
+
+        2.0 * PI * (1.0 - cos_o) + PI / 2.0 * (
+            2.0 * theta_w * sin_o - (theta_o - 2.0 * theta_w).cos()
+            - 2.0 * theta_o * sin_o + cos_o
+        )
+    }
+
+    fn cost(&self) -> f64 {
+        self.power * self.orientation() * self.bbox.area()
+    }
+}
+
+
+// cosine of the difference of two angles given by their cosines, clamped
+// to 1 when the difference would be negative
+fn cos_sub_clamped(cos_a: f64, cos_b: f64) -> f64 {
+    if cos_a >= cos_b { return 1.0 }
+    let sin_a = (1.0 - cos_a * cos_a).max(0.0).sqrt();
+    let sin_b = (1.0 - cos_b * cos_b).max(0.0).sqrt();
+    cos_a * cos_b + sin_a * sin_b
+}
+
+// smallest cone around both cones of directions, given as axis and cosine
+// of the spread angle
+fn merge_cones(a: (Vec3, f64), b: (Vec3, f64)) -> (Vec3, f64) {
+    let theta_a = a.1.clamp(-1.0, 1.0).acos();
+    let theta_b = b.1.clamp(-1.0, 1.0).acos();
+    let theta_d = a.0.dot(b.0).clamp(-1.0, 1.0).acos();
+
+    if (theta_d + theta_b).min(PI) <= theta_a { return a }
+    if (theta_d + theta_a).min(PI) <= theta_b { return b }
+
+    let theta_o = (theta_a + theta_d + theta_b) / 2.0;
+    if theta_o >= PI { return (a.0, -1.0) }
+
+    // rotate a's axis towards b's until the cone spans both
+    let k = a.0.cross(b.0);
+    if k.sq_len() <= 0.0 { return (a.0, -1.0) }
+    let k = k.unit();
+
+    let (sin_r, cos_r) = (theta_o - theta_a).sin_cos();
+    let axis = a.0 * cos_r + k.cross(a.0) * sin_r + k * (k.dot(a.0) * (1.0 - cos_r));
+
+    (axis.unit(), theta_o.cos())
+}
+
+
+#[derive(Debug)]
+struct LightNode {
+    bounds: LightBounds,
+    light: Option<usize>,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+
+// hierarchy over the lights of the scene, built once after the world is
+// divided. it picks a light for a shading point in proportion to the
+// estimated light each subtree sends there. lights without bounds, like
+// directional ones, are picked uniformly next to the tree
+#[derive(Debug)]
+pub struct LightBvh {
+    lights: Vec<Light>,
+    infinite: Vec<usize>,
+    nodes: Vec<LightNode>,
+    root: Option<usize>,
+}
+
+
+impl LightBvh {
+
+    pub fn new(lights: Vec<Light>) -> Self {
+        let mut infinite = vec![];
+        let mut bounded = vec![];
+
+        for (i, light) in lights.iter().enumerate() {
+            match light.bounds() {
+                Some(bounds) if bounds.power > 0.0 => bounded.push((i, bounds)),
+                Some(_) => (),
+                None => infinite.push(i),
+            }
+        }
+
+        let mut nodes = vec![];
+        let root = match bounded.is_empty() {
+            true => None,
+            false => Some(Self::construct(bounded, &mut nodes)),
+        };
+
+        Self { lights, infinite, nodes, root }
+    }
+
+    fn construct(mut lights: Vec<(usize, LightBounds)>, nodes: &mut Vec<LightNode>) -> usize {
+
+        if lights.len() == 1 {
+            let (light, bounds) = lights.pop().unwrap();
+            nodes.push(LightNode { bounds, light: Some(light), left: None, right: None });
+            return nodes.len() - 1
+        }
+
+        let mut bounds: Option<LightBounds> = None;
+        let mut centroid_bounds = Aabb::null();
+        for (_, b) in lights.iter() {
+            let centroid = b.bbox.centroid();
+            centroid_bounds = centroid_bounds.merge(Aabb { lower: centroid, upper: centroid });
+            bounds = Some(match bounds {
+                Some(bounds) => bounds.merge(b.clone()),
+                None => b.clone(),
+            });
+        }
+        let bounds = bounds.unwrap();
+
+        // bucket split with the lowest surface area orientation cost over
+        // all three axes, long thin splits are penalized
+        let diagonal = bounds.bbox.diagonal();
+        let longest = diagonal.x.max(diagonal.y).max(diagonal.z);
+        let mut best: Option<(f64, usize, usize)> = None;
+
+        for dim in 0..3 {
+            if centroid_bounds.upper[dim] <= centroid_bounds.lower[dim] { continue }
+
+            let bucket_of = |b: &LightBounds| {
+                let offset = centroid_bounds.offset(b.bbox.centroid());
+                ((offset[dim] * N_BUCKETS as f64) as usize).min(N_BUCKETS - 1)
+            };
+
+            let mut buckets: Vec<Option<LightBounds>> = vec![None; N_BUCKETS];
+            for (_, b) in lights.iter() {
+                let n = bucket_of(b);
+                buckets[n] = Some(match buckets[n].take() {
+                    Some(bucket) => bucket.merge(b.clone()),
+                    None => b.clone(),
+                });
+            }
+
+            let merged = |range: &[Option<LightBounds>]| {
+                range.iter().flatten().cloned().reduce(|a, b| a.merge(b))
+            };
+
+            let regularize = match diagonal[dim] > 0.0 {
+                true => longest / diagonal[dim],
+                false => 1.0,
+            };
+
+            for split in 0..(N_BUCKETS - 1) {
+                let cost = match (merged(&buckets[..=split]), merged(&buckets[split + 1..])) {
+                    (Some(l), Some(r)) => regularize * (l.cost() + r.cost()),
+                    _ => continue,
+                };
+                if best.is_none_or(|(c, _, _)| cost < c) {
+                    best = Some((cost, dim, split));
+                }
+            }
+        }
+
+        let (left_list, right_list): (Vec<_>, Vec<_>) = match best {
+            Some((_, dim, split)) => lights.into_iter().partition(|(_, b)| {
+                let offset = centroid_bounds.offset(b.bbox.centroid());
+                ((offset[dim] * N_BUCKETS as f64) as usize).min(N_BUCKETS - 1) <= split
+            }),
+            // all centroids in one spot, split evenly
+            None => {
+                let right = lights.split_off(lights.len() / 2);
+                (lights, right)
+            },
+        };
+
+        let left = Self::construct(left_list, nodes);
+        let right = Self::construct(right_list, nodes);
+        nodes.push(LightNode { bounds, light: None, left: Some(left), right: Some(right) });
+        nodes.len() - 1
+    }
+
+    // light to sample for `point`, its index among all lights and the
+    // probability it was picked with
+    pub fn pick(&self, point: Vec3) -> Option<(usize, &Light, f64)> {
+        self.pick_index(point).map(|(i, pmf)| (i, &self.lights[i], pmf))
+    }
+
+    fn pick_index(&self, point: Vec3) -> Option<(usize, f64)> {
+        let mut rng = rand::thread_rng();
+
+        let infinite = self.infinite.len();
+        let p_infinite = match self.root {
+            Some(_) => infinite as f64 / (infinite as f64 + 1.0),
+            None if infinite > 0 => 1.0,
+            None => return None,
+        };
+
+        if rng.gen::<f64>() < p_infinite {
+            let i = rng.gen_range(0..infinite);
+            return Some((self.infinite[i], p_infinite / infinite as f64))
+        }
+
+        let mut pmf = 1.0 - p_infinite;
+        let mut node = &self.nodes[self.root?];
+        if node.bounds.importance(point) <= 0.0 { return None }
+
+        loop {
+            let (left, right) = match (node.left, node.right) {
+                (Some(left), Some(right)) => (&self.nodes[left], &self.nodes[right]),
+                _ => return node.light.map(|light| (light, pmf)),
+            };
+
+            let l = left.bounds.importance(point);
+            let r = right.bounds.importance(point);
+            if l + r <= 0.0 { return None }
+
+            let p_left = l / (l + r);
+            match rng.gen::<f64>() < p_left {
+                true => { node = left; pmf *= p_left },
+                false => { node = right; pmf *= 1.0 - p_left },
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+    use crate::lights::{ PointLight, DirectionalLight };
+
+    #[test]
+    fn merged_cone_contains_both() {
+        let a = (Vec3::new(1.0, 0.0, 0.0), (0.3_f64).cos());
+        let b = (Vec3::new(0.0, 1.0, 0.0), (0.2_f64).cos());
+        let (axis, cos) = merge_cones(a, b);
+
+        // the far edges of both cones are inside the merged one
+        let theta = cos.acos();
+        let to_a = axis.dot(a.0).acos() + 0.3;
+        let to_b = axis.dot(b.0).acos() + 0.2;
+        assert!(to_a <= theta + 1e-9 && to_b <= theta + 1e-9);
+        assert!((theta - (0.3 + 0.2 + PI / 2.0) / 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn picks_match_probabilities() {
+        let white = Color::rgb(255, 255, 255);
+        let lights = vec![
+            PointLight::new(Vec3::new(0.0, 2.0, 0.0), white, 10.0),
+            PointLight::new(Vec3::new(5.0, 2.0, 0.0), white, 10.0),
+            PointLight::new(Vec3::new(-30.0, 2.0, 4.0), white, 50.0),
+            PointLight::new(Vec3::new(8.0, 9.0, -3.0), white, 1.0),
+            DirectionalLight::new(Vec3::new(0.0, -1.0, 0.0), white, 1.0),
+        ];
+        let bvh = LightBvh::new(lights);
+        let point = Vec3::new(1.0, 0.0, 0.0);
+
+        let n = 200_000;
+        let mut counts = [0usize; 5];
+        let mut pmfs = [0.0; 5];
+        for _ in 0..n {
+            let (i, pmf) = bvh.pick_index(point).unwrap();
+            counts[i] += 1;
+            pmfs[i] = pmf;
+        }
+
+        assert!((pmfs.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        for i in 0..5 {
+            assert!((counts[i] as f64 / n as f64 - pmfs[i]).abs() < 0.01);
+        }
+        // the close light is picked over the far dim one
+        assert!(pmfs[0] > pmfs[3]);
+    }
+}
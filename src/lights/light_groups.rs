@@ -0,0 +1,63 @@
+use crate::materials::Material;
+use crate::lights::Light;
+
+
+// splits the image into one pass per light group so the lights can be
+// rebalanced in compositing. every diffuse light material and every light
+// of the scene is its own group unless given a name, sources sharing a name
+// share a pass. pass 0 holds the background and all other emission
+#[derive(Debug)]
+pub struct LightGroups {
+    pub names: Vec<String>,
+    // pass of each material index and of each scene light
+    material: Vec<usize>,
+    light: Vec<usize>,
+}
+
+
+impl LightGroups {
+
+    pub fn new(materials: &[Material], lights: &[Light]) -> Self {
+        let mut names = vec![String::from("other")];
+        let mut pass = |name: String| match names.iter().position(|n| *n == name) {
+            Some(i) => i,
+            None => { names.push(name); names.len() - 1 },
+        };
+
+        let material = materials
+            .iter()
+            .enumerate()
+            .map(|(i, m)| match m {
+                Material::DiffuseLight(light) => pass(
+                    light.group.clone().unwrap_or_else(|| format!("material{}", i))
+                ),
+                _ => 0,
+            })
+            .collect();
+
+        let light = lights
+            .iter()
+            .enumerate()
+            .map(|(i, l)| pass(l.group().cloned().unwrap_or_else(|| format!("light{}", i))))
+            .collect();
+
+        Self { names, material, light }
+    }
+
+    pub fn count(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn of_material(&self, material: usize) -> usize {
+        self.material[material]
+    }
+
+    // `index` of the light among all lights, area lights come after the
+    // lights of the scene and go to the pass of their material
+    pub fn of_light(&self, index: usize, light: &Light) -> usize {
+        match light {
+            Light::AreaLight(area) => self.material[area.material],
+            _ => self.light[index],
+        }
+    }
+}
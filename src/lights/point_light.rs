@@ -0,0 +1,117 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use crate::Vec3;
+use crate::Color;
+use crate::Matrix4;
+use crate::utils::IesProfile;
+use crate::behaviors::{ Illuminate, LightSample };
+use crate::objects::Aabb;
+use crate::utils::color::luminance;
+
+use crate::lights::{ Light, LightBounds };
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Falloff {
+    // physically correct inverse square law
+    Quadratic,
+    Linear,
+    // same brightness at any distance
+    #[allow(dead_code)]
+    Constant,
+}
+
+
+impl Falloff {
+    pub fn attenuate(&self, distance: f64) -> f64 {
+        match self {
+            Falloff::Quadratic => 1.0 / (distance * distance),
+            Falloff::Linear => 1.0 / distance,
+            Falloff::Constant => 1.0,
+        }
+    }
+}
+
+
+// measured ies profile shaping a light, with the rotation from world space
+// into luminaire space
+#[derive(Debug, Clone)]
+pub struct Profile {
+    ies: Arc<IesProfile>,
+    to_local: Matrix4,
+}
+
+
+impl Profile {
+
+    pub fn new(ies: Arc<IesProfile>, orientation: Matrix4) -> Self {
+        Self { ies, to_local: orientation.inverse() }
+    }
+
+    // share of the peak intensity sent along the world direction `emitted`
+    pub fn intensity(&self, emitted: Vec3) -> f64 {
+        self.ies.intensity(self.to_local.transform_vector(emitted))
+    }
+}
+
+
+#[derive(Debug, Clone)]
+pub struct PointLight {
+    position: Vec3,
+    intensity: Vec3,
+    falloff: Falloff,
+    pub profile: Option<Profile>,
+    pub group: Option<String>,
+}
+
+
+impl PointLight {
+
+    pub fn new(position: Vec3, color: Color, intensity: f64) -> Light {
+        Self::with_falloff(position, color, intensity, Falloff::Quadratic)
+    }
+
+    pub fn with_falloff(
+        position: Vec3, color: Color, intensity: f64, falloff: Falloff
+    ) -> Light {
+        Self {
+            position,
+            intensity: color.normalize() * intensity,
+            falloff,
+            profile: None,
+            group: None,
+        }.into()
+    }
+}
+
+
+impl Illuminate for PointLight {
+
+    fn sample(&self, point: Vec3) -> Option<LightSample> {
+        let to_light = self.position - point;
+        let distance = to_light.len();
+        if distance <= 0.0 { return None }
+
+        let direction = to_light / distance;
+        let shape = self.profile.as_ref().map_or(1.0, |p| p.intensity(-direction));
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.intensity * (shape * self.falloff.attenuate(distance)),
+        })
+    }
+
+    fn bounds(&self) -> Option<LightBounds> {
+        Some(LightBounds {
+            bbox: Aabb { lower: self.position, upper: self.position },
+            power: 4.0 * PI * luminance(self.intensity),
+            axis: Vec3::new(0.0, 1.0, 0.0),
+            cos_theta_o: -1.0,
+            cos_theta_e: 0.0,
+            two_sided: false,
+        })
+    }
+
+}
@@ -0,0 +1,91 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Color;
+use crate::behaviors::{ Illuminate, LightSample };
+use crate::objects::Aabb;
+use crate::utils::color::luminance;
+
+use crate::lights::{ Light, Falloff, Profile, LightBounds };
+
+
+// point light restricted to a cone, fading out over the outer part of it
+#[derive(Debug, Clone)]
+pub struct SpotLight {
+    position: Vec3,
+    direction: Vec3,
+    intensity: Vec3,
+    falloff: Falloff,
+    cos_outer: f64,
+    cos_inner: f64,
+    pub profile: Option<Profile>,
+    pub group: Option<String>,
+}
+
+
+impl SpotLight {
+
+    // angle is the full opening angle of the cone in degrees, penumbra the
+    // share of it in 0..1 over which the light fades out towards the edge
+    pub fn new(
+        position: Vec3, look_at: Vec3, color: Color, intensity: f64,
+        angle: f64, penumbra: f64,
+    ) -> Light {
+        let half = (angle / 2.0).clamp(0.0, 90.0).to_radians();
+        Self {
+            position,
+            direction: (look_at - position).unit(),
+            intensity: color.normalize() * intensity,
+            falloff: Falloff::Quadratic,
+            cos_outer: half.cos(),
+            cos_inner: (half * (1.0 - penumbra.clamp(0.0, 1.0))).cos(),
+            profile: None,
+            group: None,
+        }.into()
+    }
+}
+
+
+impl Illuminate for SpotLight {
+
+    fn sample(&self, point: Vec3) -> Option<LightSample> {
+        let to_light = self.position - point;
+        let distance = to_light.len();
+        if distance <= 0.0 { return None }
+        let direction = to_light / distance;
+
+        let cos = -direction.dot(self.direction);
+        if cos <= self.cos_outer { return None }
+
+        // smoothstep across the penumbra
+        let cone = match cos >= self.cos_inner {
+            true => 1.0,
+            false => {
+                let t = (cos - self.cos_outer) / (self.cos_inner - self.cos_outer);
+                t * t * (3.0 - 2.0 * t)
+            },
+        };
+
+        let shape = self.profile.as_ref().map_or(1.0, |p| p.intensity(-direction));
+
+        Some(LightSample {
+            direction,
+            distance,
+            radiance: self.intensity * (cone * shape * self.falloff.attenuate(distance)),
+        })
+    }
+
+    // full strength inside the inner cone, fading out over the rest
+    fn bounds(&self) -> Option<LightBounds> {
+        let fade = self.cos_outer.acos() - self.cos_inner.acos();
+        Some(LightBounds {
+            bbox: Aabb { lower: self.position, upper: self.position },
+            power: 4.0 * PI * luminance(self.intensity),
+            axis: self.direction,
+            cos_theta_o: self.cos_inner,
+            cos_theta_e: fade.cos(),
+            two_sided: false,
+        })
+    }
+
+}
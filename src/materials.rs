@@ -4,11 +4,24 @@ use enum_dispatch::enum_dispatch;
 pub mod lambertian;
 pub mod metal;
 pub mod dielectric;
+pub mod rough_dielectric;
+pub mod principled;
+pub mod plastic;
+pub mod mix;
+pub mod subsurface;
+pub mod hair;
 pub mod diffuse_light;
+pub mod microfacet;
 
 pub use lambertian::Lambertian;
 pub use metal::Metal;
 pub use dielectric::Dielectric;
+pub use rough_dielectric::RoughDielectric;
+pub use principled::Principled;
+pub use plastic::Plastic;
+pub use mix::Mix;
+pub use subsurface::Subsurface;
+pub use hair::Hair;
 pub use diffuse_light::DiffuseLight;
 
 
@@ -16,13 +29,110 @@ pub use diffuse_light::DiffuseLight;
 use crate::Ray;
 use crate::Vec3;
 use crate::behaviors::{Scatter, IntersectResult, ScatterResult};
+use crate::textures::NormalMap;
+use crate::media::Medium;
 
 
+const MAX_MIX_DEPTH: usize = 16;
+
+
+// materials live once in the scene list, a big principled variant is fine
+#[allow(clippy::large_enum_variant)]
 #[enum_dispatch]
 #[derive(Debug)]
 pub enum Material {
     Lambertian,
     Metal,
     Dielectric,
+    RoughDielectric,
+    Principled,
+    Plastic,
+    Subsurface,
+    Hair,
     DiffuseLight,
+    Mix,
+}
+
+
+impl Material {
+
+    pub fn with_normal_map(mut self, map: NormalMap) -> Self {
+        match &mut self {
+            Material::Lambertian(m) => m.normal_map = Some(map),
+            Material::Metal(m) => m.normal_map = Some(map),
+            Material::Dielectric(m) => m.normal_map = Some(map),
+            Material::RoughDielectric(m) => m.normal_map = Some(map),
+            Material::Principled(m) => m.normal_map = Some(map),
+            Material::Plastic(m) => m.normal_map = Some(map),
+            Material::Subsurface(m) => m.surface.normal_map = Some(map),
+            _ => panic!("{:?} does not support normal maps", self),
+        }
+        self
+    }
+
+    pub fn in_group(mut self, name: &str) -> Self {
+        match &mut self {
+            Material::DiffuseLight(m) => m.group = Some(String::from(name)),
+            _ => panic!("{:?} is not a light", self),
+        }
+        self
+    }
+
+    pub fn two_sided(mut self) -> Self {
+        match &mut self {
+            Material::DiffuseLight(m) => m.two_sided = true,
+            _ => panic!("{:?} is not a light", self),
+        }
+        self
+    }
+
+    // light goes through the surface into the inside of the object, for
+    // mixes if it does through any of their materials
+    pub fn transmissive(&self, materials: &[Material], depth: usize) -> bool {
+        match self {
+            Material::Dielectric(_) | Material::RoughDielectric(_) => true,
+            Material::Principled(principled) => principled.transmissive(),
+            Material::Mix(mix) => {
+                if depth >= MAX_MIX_DEPTH {
+                    panic!("mix materials nested deeper than {}, is there a cycle?", MAX_MIX_DEPTH)
+                }
+                materials[mix.first].transmissive(materials, depth + 1)
+                    || materials[mix.second].transmissive(materials, depth + 1)
+            },
+            _ => self.interior().is_some(),
+        }
+    }
+
+    // follows mix materials down to the concrete material used for this hit
+    pub fn resolve<'a>(
+        &'a self, materials: &'a [Material], result: &IntersectResult
+    ) -> &'a Material {
+        let mut material = self;
+        for _ in 0..MAX_MIX_DEPTH {
+            match material {
+                Material::Mix(mix) => material = &materials[mix.pick(result)],
+                _ => return material,
+            }
+        }
+        panic!("mix materials nested deeper than {}, is there a cycle?", MAX_MIX_DEPTH)
+    }
+
+    // emission blended by the mix weights instead of picked, so emissive
+    // decals don't turn into noise
+    pub fn emitted(
+        &self, materials: &[Material], result: &IntersectResult, depth: usize
+    ) -> Vec3 {
+        match self {
+            Material::Mix(mix) => {
+                if depth >= MAX_MIX_DEPTH {
+                    panic!("mix materials nested deeper than {}, is there a cycle?", MAX_MIX_DEPTH)
+                }
+                let w = mix.weight(result);
+                let first = materials[mix.first].emitted(materials, result, depth + 1);
+                let second = materials[mix.second].emitted(materials, result, depth + 1);
+                (1.0 - w) * first + w * second
+            },
+            _ => self.emit(result),
+        }
+    }
 }
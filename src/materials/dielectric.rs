@@ -1,22 +1,48 @@
 
 use crate::Vec3;
 use crate::Ray;
+use crate::Color;
 use crate::behaviors::{Scatter, ScatterResult, IntersectResult};
 
 use rand::Rng;
 
 use crate::materials::Material;
+use crate::textures::NormalMap;
 
 
 #[derive(Debug, Clone)]
 pub struct Dielectric {
     refraction_index: f64,
+    // beer-lambert absorption coefficient per unit distance inside
+    absorption: Vec3,
+    pub normal_map: Option<NormalMap>,
 }
 
 
 impl Dielectric {
     pub fn new(ir: f64) -> Material {
-        Self { refraction_index: ir }.into()
+        Self {
+            refraction_index: ir,
+            absorption: Vec3::zero(),
+            normal_map: None,
+        }.into()
+    }
+
+    // light travelling `distance` units through the glass is filtered
+    // down to `color`, thicker parts get darker and more saturated
+    pub fn absorbing(ir: f64, color: Color, distance: f64) -> Material {
+        let color = color.normalize();
+        let coefficient = |c: f64| -c.max(1e-6).ln() / distance;
+
+        Self {
+            refraction_index: ir,
+            absorption: Vec3::new(
+                coefficient(color.x),
+                coefficient(color.y),
+                coefficient(color.z),
+            ),
+            normal_map: None,
+        }.into()
     }
 }
 
@@ -50,14 +76,33 @@ impl Scatter for Dielectric {
             false => result.point - (crate::BIAS * result.normal),
         };
 
+        // hitting the surface from inside means the ray travelled through
+        // the medium for the whole distance to this hit
+        let attenuation = match result.front_face {
+            true => Vec3::new(1.0, 1.0, 1.0),
+            false => {
+                let distance = result.t * ray.direction().len();
+                Vec3::new(
+                    (-self.absorption.x * distance).exp(),
+                    (-self.absorption.y * distance).exp(),
+                    (-self.absorption.z * distance).exp(),
+                )
+            },
+        };
+
         // let reflected_ray = Ray { origin: result.point, direction: dir };
         Some(ScatterResult {
             // ray: Ray { origin: result.point, direction: dir },
             ray: Ray::new(origin, dir),
-            attenuation: Vec3::new(1.0, 1.0, 1.0),
+            attenuation,
+            specular: true,
         })
     }
 
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.normal_map.as_ref()
+    }
+
 }
 
 fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
@@ -66,3 +111,76 @@ fn reflectance(cosine: f64, ref_idx: f64) -> f64 {
     let r0_sq = r0 * r0;
     r0_sq + (1.0 - r0_sq) * (1.0 - cosine).powf(5.0)
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Matrix4;
+    use crate::objects::{ Object, Tlas, Sealed, Instance, Prototype, helpers };
+    use crate::materials::{ Lambertian, Principled };
+
+    // light refracted into a glass cube is tinted by the distance it
+    // travelled inside when it reaches the far side, the cube sits around
+    // the origin with the ray coming in along -z
+    fn absorbs(materials: &[Material], mut cube: Object) {
+        cube.seal(materials, None, &mut Sealed::new());
+        let tlas = Tlas::new(vec![cube]);
+
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, -1.0));
+        let entry = tlas.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!(entry.front_face);
+        let glass = &materials[entry.material];
+
+        // reflected now and then, tried until it goes in
+        let inside = (0..1000)
+            .filter_map(|_| glass.scatter(&ray, entry.clone()))
+            .find(|scattered| scattered.ray.direction().z < 0.0)
+            .unwrap();
+        assert_eq!(inside.attenuation, Vec3::new(1.0, 1.0, 1.0));
+
+        let exit = tlas.intersect(&inside.ray, 0.001, f64::INFINITY).unwrap();
+        assert!(!exit.front_face);
+        let distance = exit.t * inside.ray.direction().len();
+        assert!((distance - 2.0).abs() < 1e-2);
+
+        let out = glass.scatter(&inside.ray, exit).unwrap();
+        let expected = Color::rgb(255, 128, 64).normalize();
+        for i in 0..3 {
+            assert!((out.attenuation[i] - expected[i].powf(distance)).abs() < 1e-9);
+        }
+        assert!(out.attenuation.y < 0.3 && out.attenuation.x > 0.99);
+    }
+
+    #[test]
+    fn dielectric_mesh_absorbs() {
+        let materials = vec![Dielectric::absorbing(1.5, Color::rgb(255, 128, 64), 1.0)];
+        absorbs(
+            &materials,
+            helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0).into(),
+        );
+    }
+
+    // glass given to an instance by overriding the material of its prototype
+    #[test]
+    fn dielectric_instance_absorbs() {
+        let materials = vec![
+            Lambertian::grey(),
+            Dielectric::absorbing(1.5, Color::rgb(255, 128, 64), 1.0),
+        ];
+        let cube = Prototype::new(vec![
+            helpers::cuboid(Vec3::new(3.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0).into(),
+        ]);
+        absorbs(
+            &materials,
+            Instance::with_material(&cube, Matrix4::translate(-4.0, 0.0, 0.0), 1),
+        );
+    }
+
+    #[test]
+    fn dielectric_principled_transmissive() {
+        let white = Color::rgb(255, 255, 255);
+        assert!(Principled::builder(white).transmission(1.0).transmissive());
+        assert!(!Principled::builder(white).transmissive());
+    }
+}
@@ -1,4 +1,3 @@
-
 use crate::Vec3;
 use crate::Ray;
 use crate::Color;
@@ -9,6 +8,11 @@ use crate::materials::Material;
 #[derive(Debug, Clone)]
 pub struct DiffuseLight {
     albedo: Vec3,
+    // emit from the back face as well, off by default so quad lights don't
+    // leak light behind them
+    pub two_sided: bool,
+    // light group pass the emission is written to
+    pub group: Option<String>,
 }
 
 
@@ -17,14 +21,22 @@ impl DiffuseLight {
     pub fn new(color: Color, intensity: f64) -> Material {
         Self {
             albedo: color.normalize() * intensity,
+            two_sided: false,
+            group: None,
         }.into()
     }
 
     pub fn white(intensity: f64) -> Material {
         Self {
             albedo: Vec3::new(1.0, 1.0, 1.0) * intensity,
+            two_sided: false,
+            group: None,
         }.into()
     }
+
+    pub fn radiance(&self) -> Vec3 {
+        self.albedo
+    }
 }
 
 
@@ -34,8 +46,11 @@ impl Scatter for DiffuseLight {
         None
     }
 
-    fn emit(&self) -> Vec3 {
-        self.albedo
+    fn emit(&self, result: &IntersectResult) -> Vec3 {
+        match result.front_face || self.two_sided {
+            true => self.albedo,
+            false => Vec3::zero(),
+        }
     }
 
 }
@@ -0,0 +1,176 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::Color;
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult, Evaluate};
+
+use crate::materials::Material;
+use crate::materials::microfacet::Frame;
+use crate::textures::{ Texture, SolidColor };
+
+use rand::Rng;
+
+
+// the scales on a fiber tilt the highlight towards the root
+const CUTICLE_TILT: f64 = 2.0 * PI / 180.0;
+
+
+// fiber shaded around its direction instead of a normal, after Kajiya and
+// Kay. light scatters diffusely all around the fiber with its color, or
+// into a colorless highlight on the cone of mirror directions along the
+// fiber. angles are measured from the plane square to the fiber, both
+// lobes are densities over the sphere scaled by their share, so sampling
+// them is exact. on other objects the fiber runs along the tangent
+#[derive(Debug, Clone)]
+pub struct Hair {
+    color: Texture,
+    // share of light going into the highlight
+    specular: f64,
+    // half width of the highlight, in radians
+    roughness: f64,
+}
+
+
+impl Hair {
+
+    pub fn new(color: Color, specular: f64, roughness: f64) -> Material {
+        Self::textured(SolidColor::new(color), specular, roughness)
+    }
+
+    pub fn textured(color: Texture, specular: f64, roughness: f64) -> Material {
+        Self {
+            color,
+            specular: specular.clamp(0.0, 1.0),
+            roughness: roughness.max(1e-3),
+        }.into()
+    }
+}
+
+
+impl Hair {
+
+    // local z along the fiber
+    fn frame(result: &IntersectResult) -> Frame {
+        let along = match result.tangent.near_zero() {
+            true => result.normal.orthonormal_basis().0,
+            false => result.tangent.unit(),
+        };
+        Frame::new(along)
+    }
+
+    // density over the angle from the plane square to the fiber, a tent
+    // around the mirrored outgoing angle. the parts reaching past the
+    // poles fold back onto the sphere
+    fn highlight(&self, theta_o: f64, theta_i: f64) -> f64 {
+        let center = -theta_o + 2.0 * CUTICLE_TILT;
+        let tent = |theta: f64| {
+            (self.roughness - (theta - center).abs()).max(0.0) / (self.roughness * self.roughness)
+        };
+        tent(theta_i) + tent(PI - theta_i) + tent(-PI - theta_i)
+    }
+
+    // densities over the sphere of both lobes for a local incoming direction
+    fn densities(&self, wo: Vec3, wi: Vec3) -> (f64, f64) {
+        let cos_i = (wi.x * wi.x + wi.y * wi.y).sqrt();
+        let theta = |w: Vec3| w.z.clamp(-1.0, 1.0).asin();
+
+        let highlight = match cos_i > 1e-6 {
+            true => self.highlight(theta(wo), theta(wi)) / (2.0 * PI * cos_i),
+            false => 0.0,
+        };
+        (highlight, cos_i / (PI * PI))
+    }
+}
+
+
+impl Scatter for Hair {
+
+    fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult> {
+        let frame = Hair::frame(&result);
+        let wo = frame.to_local(-ray.direction().unit());
+        let mut rng = rand::thread_rng();
+
+        let theta = match rng.gen::<f64>() < self.specular {
+            true => {
+                let center = -wo.z.clamp(-1.0, 1.0).asin() + 2.0 * CUTICLE_TILT;
+                let theta = center + self.roughness * (rng.gen::<f64>() - rng.gen::<f64>());
+                match theta {
+                    t if t > PI / 2.0 => PI - t,
+                    t if t < -PI / 2.0 => -PI - t,
+                    t => t,
+                }
+            },
+            // cos^2 over the angle, by rejection
+            false => loop {
+                let theta = PI * (rng.gen::<f64>() - 0.5);
+                if rng.gen::<f64>() < theta.cos().powi(2) { break theta }
+            },
+        };
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let wi = Vec3::new(theta.cos() * phi.cos(), theta.cos() * phi.sin(), theta.sin());
+
+        let (highlight, diffuse) = self.densities(wo, wi);
+        let pdf = self.specular * highlight + (1.0 - self.specular) * diffuse;
+        if pdf <= 0.0 { return None }
+
+        let color = self.color.value(result.u, result.v, result.point);
+        let f = Vec3::new(1.0, 1.0, 1.0) * (self.specular * highlight)
+            + color * ((1.0 - self.specular) * diffuse);
+
+        let direction = frame.to_world(wi);
+        Some(ScatterResult {
+            ray: Ray::new(result.point + (crate::BIAS * direction), direction),
+            attenuation: f / pdf,
+            specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        let frame = Hair::frame(result);
+        let wo = frame.to_local(-ray.direction().unit());
+        let (highlight, diffuse) = self.densities(wo, frame.to_local(wi));
+
+        let color = self.color.value(result.u, result.v, result.point);
+        Vec3::new(1.0, 1.0, 1.0) * (self.specular * highlight)
+            + color * ((1.0 - self.specular) * diffuse)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // both lobes hold exactly their share of the light
+    #[test]
+    fn hair_lobes_integrate() {
+        let Material::Hair(hair) = Hair::new(Color::rgb(255, 255, 255), 0.5, 0.3) else {
+            panic!("not hair")
+        };
+        // the second highlight reaches past the pole and folds back
+        for wo in [Vec3::new(0.6, 0.0, 0.8), Vec3::new(0.1, 0.0, 0.995).unit()] {
+            let (highlight, diffuse) = integrate(&hair, wo);
+            assert!((highlight - 1.0).abs() < 1e-2, "highlight {}", highlight);
+            assert!((diffuse - 1.0).abs() < 1e-2, "diffuse {}", diffuse);
+        }
+    }
+
+    // midpoint rule over the angle from the fiber plane and around it
+    fn integrate(hair: &Hair, wo: Vec3) -> (f64, f64) {
+        let (n_theta, n_phi) = (2000, 64);
+        let (mut highlight, mut diffuse) = (0.0, 0.0);
+        for i in 0..n_theta {
+            let theta = -PI / 2.0 + PI * (i as f64 + 0.5) / n_theta as f64;
+            for j in 0..n_phi {
+                let phi = 2.0 * PI * (j as f64 + 0.5) / n_phi as f64;
+                let wi = Vec3::new(theta.cos() * phi.cos(), theta.cos() * phi.sin(), theta.sin());
+                let area = theta.cos() * (PI / n_theta as f64) * (2.0 * PI / n_phi as f64);
+                let (h, d) = hair.densities(wo, wi);
+                highlight += h * area;
+                diffuse += d * area;
+            }
+        }
+        (highlight, diffuse)
+    }
+}
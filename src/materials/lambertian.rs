@@ -1,26 +1,37 @@
 
+use std::f64::consts::PI;
+
 use crate::Vec3;
 use crate::Ray;
 use crate::Color;
-use crate::behaviors::{Scatter, ScatterResult, IntersectResult};
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult, Evaluate};
 
 use crate::materials::Material;
+use crate::textures::{ Texture, SolidColor, NormalMap };
 
 
 #[derive(Debug, Clone)]
 pub struct Lambertian {
-    albedo: Vec3,
+    albedo: Texture,
+    pub normal_map: Option<NormalMap>,
 }
 
 
 impl Lambertian {
 
     pub fn new(color: Color) -> Material {
-        Self { albedo: color.normalize() }.into()
+        Self { albedo: SolidColor::new(color), normal_map: None }.into()
     }
 
     pub fn grey() -> Material {
-        Self { albedo: Vec3::new(0.5, 0.5, 0.5) }.into()
+        Self {
+            albedo: SolidColor::from_vec3(Vec3::new(0.5, 0.5, 0.5)),
+            normal_map: None,
+        }.into()
+    }
+
+    pub fn textured(albedo: Texture) -> Material {
+        Self { albedo, normal_map: None }.into()
     }
 
 }
@@ -61,8 +72,19 @@ impl Scatter for Lambertian {
 
         Some(ScatterResult {
             ray: scattered_ray,
-            attenuation: self.albedo,
+            attenuation: self.albedo.value(result.u, result.v, result.point),
+            specular: false,
         })
     }
 
+    fn eval(&self, _: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        let cos = result.normal.dot(wi);
+        if cos <= 0.0 { return Vec3::zero() }
+        self.albedo.value(result.u, result.v, result.point) * (cos / PI)
+    }
+
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.normal_map.as_ref()
+    }
+
 }
@@ -5,23 +5,90 @@ use crate::Color;
 use crate::behaviors::{Scatter, ScatterResult, IntersectResult};
 
 use crate::materials::Material;
+use crate::materials::microfacet::{ Frame, Ggx, fresnel_schlick, fresnel_conductor };
+use crate::textures::NormalMap;
 
+use rand::Rng;
+
+
+#[derive(Debug, Clone, Copy)]
+pub enum Fresnel {
+    // artist friendly reflectance color at normal incidence
+    Schlick(Vec3),
+    // measured complex index of refraction eta + ik per color channel
+    Conductor { eta: Vec3, k: Vec3 },
+}
+
+impl Fresnel {
+    fn eval(&self, cos_i: f64) -> Vec3 {
+        match *self {
+            Fresnel::Schlick(f0) => fresnel_schlick(cos_i, f0),
+            Fresnel::Conductor { eta, k } => fresnel_conductor(cos_i, eta, k),
+        }
+    }
+}
+
+
+// ggx microfacet conductor
 #[derive(Debug, Clone)]
 pub struct Metal {
-    albedo: Vec3,
-    fuzz: f64,
+    fresnel: Fresnel,
+    distribution: Ggx,
+    pub normal_map: Option<NormalMap>,
 }
 
 
 impl Metal {
 
+    // the old fuzz parameter maps directly to perceptual roughness
     pub fn new(color: Color, fuzz: f64) -> Material {
         Self {
-            albedo: color.normalize(),
-            fuzz: if fuzz < 1.0 { fuzz } else { 1.0 }
+            fresnel: Fresnel::Schlick(color.normalize()),
+            distribution: Ggx::from_roughness(fuzz),
+            normal_map: None,
         }.into()
     }
 
+    pub fn conductor(eta: Vec3, k: Vec3, roughness: f64) -> Material {
+        Self {
+            fresnel: Fresnel::Conductor { eta, k },
+            distribution: Ggx::from_roughness(roughness),
+            normal_map: None,
+        }.into()
+    }
+
+    pub fn gold(roughness: f64) -> Material {
+        Self::conductor(
+            Vec3::new(0.143, 0.374, 1.442),
+            Vec3::new(3.983, 2.385, 1.603),
+            roughness,
+        )
+    }
+
+    pub fn copper(roughness: f64) -> Material {
+        Self::conductor(
+            Vec3::new(0.200, 0.924, 1.102),
+            Vec3::new(3.912, 2.452, 2.142),
+            roughness,
+        )
+    }
+
+    pub fn aluminium(roughness: f64) -> Material {
+        Self::conductor(
+            Vec3::new(1.657, 0.880, 0.521),
+            Vec3::new(9.224, 6.270, 4.837),
+            roughness,
+        )
+    }
+
+    pub fn silver(roughness: f64) -> Material {
+        Self::conductor(
+            Vec3::new(0.155, 0.117, 0.138),
+            Vec3::new(4.828, 3.122, 2.147),
+            roughness,
+        )
+    }
+
     // pub fn grey(fuzz: f64) -> Material {
     //     Self {
     //         albedo: Vec3::new(0.5, 0.5, 0.5),
@@ -35,33 +102,50 @@ impl Scatter for Metal {
 
     fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult> {
 
-        let reflect_dir = Vec3::reflect(ray.direction().unit(), result.normal);
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        if wo.z <= 0.0 { return None }
+
+        let origin = result.point + (crate::BIAS * result.normal);
 
-        // hemisphere diffusion
-        let rnd_vector = Vec3::random_in_hemisphere(result.normal);
-        let reflect_dir = reflect_dir + (self.fuzz * rnd_vector);
+        if self.distribution.is_smooth() {
+            let wi = Vec3::new(-wo.x, -wo.y, wo.z);
+            return Some(ScatterResult {
+                ray: Ray::new(origin, frame.to_world(wi)),
+                attenuation: self.fresnel.eval(wo.z),
+                specular: true,
+            })
+        }
 
-        // random in sphere approx diffusion
-        // let rnd_vector = Vec3::random_in_unit_sphere().unit();
-        // let reflect_dir = reflect_dir + (self.fuzz * (rnd_vector + result.normal));
+        // sample a visible microfacet normal and mirror around it, the
+        // sample weight f * cos / pdf reduces to F * G2 / G1
+        let mut rng = rand::thread_rng();
+        let m = self.distribution.sample_visible_normal(wo, rng.gen(), rng.gen());
+        let wi = Vec3::reflect(-wo, m);
 
-        // let reflected_ray = Ray {
-        //     origin: result.point,
-        //     direction: reflect_dir,
-        // };
+        // reflected below the surface, multiple scattering is not modelled
+        if wi.z <= 0.0 { return None }
 
-        let reflected_ray = Ray::new(
-            result.point + (crate::BIAS * result.normal),
-            reflect_dir
-        );
+        let weight = self.distribution.g2(wo, wi) / self.distribution.g1(wo);
 
-        match reflected_ray.direction().dot(result.normal) > 0.0 {
-            true => Some(ScatterResult {
-                ray: reflected_ray,
-                attenuation: self.albedo,
-            }),
-            false => None,
-        }
+        Some(ScatterResult {
+            ray: Ray::new(origin, frame.to_world(wi)),
+            attenuation: self.fresnel.eval(wo.dot(m)) * weight,
+            specular: self.distribution.is_sharp(),
+        })
+    }
+
+    fn eval(&self, ray: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        let wi = frame.to_local(wi);
+
+        let h = (wo + wi).unit();
+        self.fresnel.eval(wo.dot(h)) * self.distribution.reflection(wo, wi)
+    }
+
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.normal_map.as_ref()
     }
 
 }
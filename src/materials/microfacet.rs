@@ -0,0 +1,253 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+
+
+// orthonormal shading frame around a normal, local z is the normal
+#[derive(Debug, Clone)]
+pub struct Frame {
+    t: Vec3,
+    b: Vec3,
+    n: Vec3,
+}
+
+
+impl Frame {
+
+    pub fn new(normal: Vec3) -> Self {
+        let (t, b) = normal.orthonormal_basis();
+        Self { t, b, n: normal }
+    }
+
+    pub fn to_local(&self, v: Vec3) -> Vec3 {
+        Vec3::new(v.dot(self.t), v.dot(self.b), v.dot(self.n))
+    }
+
+    pub fn to_world(&self, v: Vec3) -> Vec3 {
+        (v.x * self.t) + (v.y * self.b) + (v.z * self.n)
+    }
+}
+
+
+
+// ggx / trowbridge-reitz distribution with smith masking-shadowing,
+// all directions in the local shading frame pointing away from the surface
+#[derive(Debug, Clone, Copy)]
+pub struct Ggx {
+    pub alpha: f64,
+}
+
+
+impl Ggx {
+
+    // perceptual roughness in 0..1 to alpha
+    pub fn from_roughness(roughness: f64) -> Self {
+        let roughness = roughness.clamp(0.0, 1.0);
+        Self { alpha: (roughness * roughness).max(1e-4) }
+    }
+
+    // below this the lobe is treated as a perfect mirror
+    pub fn is_smooth(&self) -> bool {
+        self.alpha < 1e-3
+    }
+
+    // too narrow for light sampling to hit the highlight, such lobes are
+    // left to the rays they scatter
+    pub fn is_sharp(&self) -> bool {
+        self.alpha < 1e-2
+    }
+
+    pub fn d(&self, m: Vec3) -> f64 {
+        if m.z <= 0.0 { return 0.0 }
+        let a2 = self.alpha * self.alpha;
+        let t = m.z * m.z * (a2 - 1.0) + 1.0;
+        a2 / (PI * t * t)
+    }
+
+    pub fn lambda(&self, w: Vec3) -> f64 {
+        let z2 = w.z * w.z;
+        if z2 <= 0.0 { return f64::INFINITY }
+        let tan2 = (1.0 - z2).max(0.0) / z2;
+        0.5 * (-1.0 + (1.0 + self.alpha * self.alpha * tan2).sqrt())
+    }
+
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    // height correlated masking-shadowing
+    pub fn g2(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    // specular reflection brdf times cosine, D * G2 / (4 cos_o) without the
+    // fresnel term, zero for sharp lobes which light sampling leaves out
+    pub fn reflection(&self, wo: Vec3, wi: Vec3) -> f64 {
+        if self.is_sharp() || wo.z <= 0.0 || wi.z <= 0.0 { return 0.0 }
+        let h = (wo + wi).unit();
+        self.d(h) * self.g2(wo, wi) / (4.0 * wo.z)
+    }
+
+    // visible normal sampling (Heitz 2018), wo must be in the upper hemisphere
+    pub fn sample_visible_normal(&self, wo: Vec3, u1: f64, u2: f64) -> Vec3 {
+        let vh = Vec3::new(self.alpha * wo.x, self.alpha * wo.y, wo.z).unit();
+
+        let lensq = vh.x * vh.x + vh.y * vh.y;
+        let t1 = match lensq > 0.0 {
+            true => Vec3::new(-vh.y, vh.x, 0.0) / lensq.sqrt(),
+            false => Vec3::new(1.0, 0.0, 0.0),
+        };
+        let t2 = vh.cross(t1);
+
+        let r = u1.sqrt();
+        let phi = 2.0 * PI * u2;
+        let p1 = r * phi.cos();
+        let p2 = r * phi.sin();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * p2;
+
+        let nh = p1 * t1 + p2 * t2
+            + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+
+        Vec3::new(self.alpha * nh.x, self.alpha * nh.y, nh.z.max(1e-6)).unit()
+    }
+}
+
+
+
+// reflectance at normal incidence blended towards white at grazing angles
+pub fn fresnel_schlick(cos_i: f64, f0: Vec3) -> Vec3 {
+    let f = (1.0 - cos_i.clamp(0.0, 1.0)).powi(5);
+    f0 + (Vec3::new(1.0, 1.0, 1.0) - f0) * f
+}
+
+// exact unpolarized fresnel reflectance of a dielectric interface, eta is
+// the ratio of incident over transmitted ior, 1.0 on total internal reflection
+pub fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = eta * eta * (1.0 - cos_i * cos_i);
+    if sin2_t >= 1.0 { return 1.0 }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let rs = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let rp = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    0.5 * (rs * rs + rp * rp)
+}
+
+// fresnel reflectance averaged over a cosine weighted hemisphere, the share
+// of diffuse light reflected back at an interface with relative ior eta
+pub fn fresnel_diffuse(eta: f64) -> f64 {
+    let n = 1000;
+    let mut sum = 0.0;
+    for i in 0..n {
+        let cos_i = (i as f64 + 0.5) / n as f64;
+        sum += fresnel_dielectric(cos_i, eta) * 2.0 * cos_i;
+    }
+    sum / n as f64
+}
+
+// exact unpolarized fresnel reflectance of a conductor with complex ior
+// eta + ik, evaluated per color channel
+pub fn fresnel_conductor(cos_i: f64, eta: Vec3, k: Vec3) -> Vec3 {
+    let mut result = Vec3::zero();
+    for i in 0..3 {
+        result[i] = fresnel_conductor_channel(cos_i.clamp(0.0, 1.0), eta[i], k[i]);
+    }
+    result
+}
+
+fn fresnel_conductor_channel(cos_i: f64, eta: f64, k: f64) -> f64 {
+    let cos2 = cos_i * cos_i;
+    let sin2 = 1.0 - cos2;
+    let eta2 = eta * eta;
+    let k2 = k * k;
+
+    let t0 = eta2 - k2 - sin2;
+    let a2b2 = (t0 * t0 + 4.0 * eta2 * k2).sqrt();
+    let t1 = a2b2 + cos2;
+    let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+    let t2 = 2.0 * cos_i * a;
+    let rs = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let rp = rs * (t3 - t4) / (t3 + t4);
+
+    0.5 * (rp + rs)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn ggx_normalized() {
+        // projected microfacet area integrates to 1 over the hemisphere
+        for roughness in [0.5, 0.8, 1.0] {
+            let ggx = Ggx::from_roughness(roughness);
+            let n = 400;
+            let mut sum = 0.0;
+            for i in 0..n {
+                for j in 0..n {
+                    let z = (i as f64 + 0.5) / n as f64;
+                    let phi = 2.0 * PI * (j as f64 + 0.5) / n as f64;
+                    let r = (1.0 - z * z).sqrt();
+                    let m = Vec3::new(r * phi.cos(), r * phi.sin(), z);
+                    sum += ggx.d(m) * m.z;
+                }
+            }
+            let integral = sum * 2.0 * PI / (n * n) as f64;
+            assert!((integral - 1.0).abs() < 0.01, "{} {}", roughness, integral);
+        }
+    }
+
+    #[test]
+    fn visible_normals_face_viewer() {
+        let ggx = Ggx::from_roughness(0.7);
+        let wo = Vec3::new(0.6, 0.0, 0.8);
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let m = ggx.sample_visible_normal(wo, rng.gen(), rng.gen());
+            assert!(m.z > 0.0);
+            assert!(wo.dot(m) >= -1e-9);
+            assert!((m.len() - 1.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn dielectric_fresnel_limits() {
+        // 4% reflectance for glass at normal incidence
+        assert!((fresnel_dielectric(1.0, 1.0 / 1.5) - 0.04).abs() < 1e-9);
+        assert!((fresnel_dielectric(0.0, 1.0 / 1.5) - 1.0).abs() < 1e-9);
+        // total internal reflection from inside past the critical angle
+        assert_eq!(fresnel_dielectric(0.5, 1.5), 1.0);
+        assert!(fresnel_dielectric(0.9, 1.5) < 1.0);
+    }
+
+    #[test]
+    fn diffuse_fresnel_reciprocity() {
+        // about 9% of diffuse light reflects off glass from outside, inside
+        // the rest follows from energy conservation: 1 - (1 - Fext) / eta^2
+        let external = fresnel_diffuse(1.0 / 1.5);
+        let internal = fresnel_diffuse(1.5);
+        assert!((external - 0.092).abs() < 0.002);
+        assert!((internal - (1.0 - (1.0 - external) / 2.25)).abs() < 0.002);
+    }
+
+    #[test]
+    fn conductor_fresnel_limits() {
+        let eta = Vec3::new(0.143, 0.374, 1.442);
+        let k = Vec3::new(3.983, 2.385, 1.603);
+        let grazing = fresnel_conductor(0.0, eta, k);
+        let normal = fresnel_conductor(1.0, eta, k);
+        for i in 0..3 {
+            assert!((grazing[i] - 1.0).abs() < 1e-9);
+            let expected = ((eta[i] - 1.0).powi(2) + k[i] * k[i])
+                / ((eta[i] + 1.0).powi(2) + k[i] * k[i]);
+            assert!((normal[i] - expected).abs() < 1e-9);
+        }
+    }
+}
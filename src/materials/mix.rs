@@ -0,0 +1,49 @@
+use crate::Ray;
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult, Evaluate};
+
+use crate::materials::Material;
+use crate::textures::Texture;
+
+use rand::Rng;
+
+
+// blends two materials of the scene by their index in the material list,
+// weight 0 is fully `first` and 1 fully `second`. indexes instead of owned
+// materials keep nested mixes cheap to share and free of reference cycles
+#[derive(Debug, Clone)]
+pub struct Mix {
+    pub first: usize,
+    pub second: usize,
+    weight: Texture,
+}
+
+
+impl Mix {
+
+    pub fn new(first: usize, second: usize, weight: impl Into<Texture>) -> Material {
+        Self { first, second, weight: weight.into() }.into()
+    }
+
+    pub fn weight(&self, result: &IntersectResult) -> f64 {
+        self.weight.value(result.u, result.v, result.point).x.clamp(0.0, 1.0)
+    }
+
+    // stochastic pick, the blend comes from averaging over samples
+    pub fn pick(&self, result: &IntersectResult) -> usize {
+        match rand::thread_rng().gen::<f64>() < self.weight(result) {
+            true => self.second,
+            false => self.first,
+        }
+    }
+}
+
+
+impl Scatter for Mix {
+
+    // mixes are resolved to one of their materials before scattering and
+    // their emission is blended, see Material::resolve and Material::emitted
+    fn scatter(&self, _: &Ray, _: IntersectResult) -> Option<ScatterResult> {
+        None
+    }
+
+}
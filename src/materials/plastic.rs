@@ -0,0 +1,135 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::Color;
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult, Evaluate};
+
+use crate::materials::Material;
+use crate::materials::microfacet::{ Frame, Ggx, fresnel_dielectric, fresnel_diffuse };
+use crate::textures::{ Texture, SolidColor, NormalMap };
+
+use rand::Rng;
+
+
+// ior of the coating, typical for varnish and plastics
+const COATING_IOR: f64 = 1.5;
+
+
+// lambertian base under a smooth or rough dielectric coating. light that
+// gets through the coating bounces between base and coating, that series
+// is summed up analytically instead of being traced (Weidlich, Wilkie 2007)
+#[derive(Debug, Clone)]
+pub struct Plastic {
+    albedo: Texture,
+    distribution: Ggx,
+    // share of diffuse light the coating reflects back down onto the base
+    internal_reflectance: f64,
+    pub normal_map: Option<NormalMap>,
+}
+
+
+impl Plastic {
+
+    pub fn new(color: Color, roughness: f64) -> Material {
+        Self::textured(SolidColor::new(color), roughness)
+    }
+
+    pub fn textured(albedo: Texture, roughness: f64) -> Material {
+        Self {
+            albedo,
+            distribution: Ggx::from_roughness(roughness),
+            internal_reflectance: fresnel_diffuse(COATING_IOR),
+            normal_map: None,
+        }.into()
+    }
+}
+
+
+impl Plastic {
+
+    // each internal bounce is reflected by the base with albedo and sent
+    // back down by the coating, the geometric series per color channel
+    fn base(&self, result: &IntersectResult) -> Vec3 {
+        let albedo = self.albedo.value(result.u, result.v, result.point);
+        let bounced = |a: f64| a / (1.0 - self.internal_reflectance * a);
+        Vec3::new(bounced(albedo.x), bounced(albedo.y), bounced(albedo.z))
+    }
+}
+
+
+impl Scatter for Plastic {
+
+    fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult> {
+
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        if wo.z <= 0.0 { return None }
+
+        let origin = result.point + (crate::BIAS * result.normal);
+        let eta = 1.0 / COATING_IOR;
+        let mut rng = rand::thread_rng();
+
+        // picking the coating with the probability of its macro surface
+        // reflectance cancels the fresnel terms on both lobes
+        let coating_chance = fresnel_dielectric(wo.z, eta);
+
+        if rng.gen::<f64>() < coating_chance {
+            let m = match self.distribution.is_smooth() {
+                true => Vec3::new(0.0, 0.0, 1.0),
+                false => self.distribution.sample_visible_normal(wo, rng.gen(), rng.gen()),
+            };
+            let wi = Vec3::reflect(-wo, m);
+            if wi.z <= 0.0 { return None }
+
+            let weight = match self.distribution.is_smooth() {
+                true => 1.0,
+                false => fresnel_dielectric(wo.dot(m), eta) / coating_chance
+                    * self.distribution.g2(wo, wi) / self.distribution.g1(wo),
+            };
+            return Some(ScatterResult {
+                ray: Ray::new(origin, frame.to_world(wi)),
+                attenuation: Vec3::new(weight, weight, weight),
+                specular: self.distribution.is_sharp(),
+            })
+        }
+
+        // cosine weighted diffuse bounce under the coating
+        let wi = (Vec3::new(0.0, 0.0, 1.0) + Vec3::random_in_unit_sphere().unit()).unit();
+        if wi.z <= 0.0 || wi.z.is_nan() { return None }
+
+        let base = self.base(&result);
+
+        // light leaving through the coating is refracted into a wider solid
+        // angle, which spreads the radiance by eta squared
+        let exit = (1.0 - fresnel_dielectric(wi.z, eta)) * eta * eta;
+
+        Some(ScatterResult {
+            ray: Ray::new(origin, frame.to_world(wi)),
+            attenuation: base * exit,
+            specular: false,
+        })
+    }
+
+    fn eval(&self, ray: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        let wi = frame.to_local(wi);
+        if wo.z <= 0.0 || wi.z <= 0.0 { return Vec3::zero() }
+
+        let eta = 1.0 / COATING_IOR;
+        let h = (wo + wi).unit();
+        let coating = fresnel_dielectric(wo.dot(h), eta) * self.distribution.reflection(wo, wi);
+
+        let through = (1.0 - fresnel_dielectric(wo.z, eta))
+            * (1.0 - fresnel_dielectric(wi.z, eta)) * eta * eta;
+        let diffuse = self.base(result) * (through * wi.z / PI);
+
+        Vec3::new(coating, coating, coating) + diffuse
+    }
+
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.normal_map.as_ref()
+    }
+
+}
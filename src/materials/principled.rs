@@ -0,0 +1,357 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult, Evaluate};
+use crate::utils::{ lerp, color::luminance };
+
+use crate::materials::Material;
+use crate::materials::microfacet::{ Frame, Ggx, fresnel_schlick, fresnel_dielectric };
+use crate::textures::{ Texture, NormalMap };
+
+use rand::Rng;
+
+
+// ior of the clearcoat layer, fixed like in the disney model
+const CLEARCOAT_IOR: f64 = 1.5;
+
+
+// disney style principled bsdf (Burley 2012, 2015). every parameter is a
+// texture, scalar parameters use the red channel. `new` gives the default
+// surface, `builder` starts chained setters finished with `.into()`
+#[derive(Debug, Clone)]
+pub struct Principled {
+    base_color: Texture,
+    metallic: Texture,
+    roughness: Texture,
+    specular: Texture,
+    specular_tint: Texture,
+    sheen: Texture,
+    clearcoat: Texture,
+    clearcoat_gloss: Texture,
+    transmission: Texture,
+    ior: Texture,
+    emission: Texture,
+    emission_strength: f64,
+    pub normal_map: Option<NormalMap>,
+}
+
+
+impl Principled {
+
+    pub fn new(base_color: impl Into<Texture>) -> Material {
+        Self::builder(base_color).into()
+    }
+
+    pub fn builder(base_color: impl Into<Texture>) -> Self {
+        Self {
+            base_color: base_color.into(),
+            metallic: 0.0.into(),
+            roughness: 0.5.into(),
+            specular: 0.5.into(),
+            specular_tint: 0.0.into(),
+            sheen: 0.0.into(),
+            clearcoat: 0.0.into(),
+            clearcoat_gloss: 1.0.into(),
+            transmission: 0.0.into(),
+            ior: 1.45.into(),
+            emission: 0.0.into(),
+            emission_strength: 1.0,
+            normal_map: None,
+        }
+    }
+
+    pub fn metallic(mut self, value: impl Into<Texture>) -> Self {
+        self.metallic = value.into();
+        self
+    }
+
+    pub fn roughness(mut self, value: impl Into<Texture>) -> Self {
+        self.roughness = value.into();
+        self
+    }
+
+    // 0.5 is 4% reflectance at normal incidence, the usual dielectric
+    pub fn specular(mut self, value: impl Into<Texture>) -> Self {
+        self.specular = value.into();
+        self
+    }
+
+    // tints the dielectric reflection towards the base color
+    pub fn specular_tint(mut self, value: impl Into<Texture>) -> Self {
+        self.specular_tint = value.into();
+        self
+    }
+
+    // soft grazing retro-reflection for cloth
+    pub fn sheen(mut self, value: impl Into<Texture>) -> Self {
+        self.sheen = value.into();
+        self
+    }
+
+    pub fn clearcoat(mut self, value: impl Into<Texture>) -> Self {
+        self.clearcoat = value.into();
+        self
+    }
+
+    pub fn clearcoat_gloss(mut self, value: impl Into<Texture>) -> Self {
+        self.clearcoat_gloss = value.into();
+        self
+    }
+
+    pub fn transmission(mut self, value: impl Into<Texture>) -> Self {
+        self.transmission = value.into();
+        self
+    }
+
+    pub fn ior(mut self, value: impl Into<Texture>) -> Self {
+        self.ior = value.into();
+        self
+    }
+
+    pub fn emission(mut self, color: impl Into<Texture>, strength: f64) -> Self {
+        self.emission = color.into();
+        self.emission_strength = strength;
+        self
+    }
+
+    // a textured transmission may let light in anywhere
+    pub fn transmissive(&self) -> bool {
+        match &self.transmission {
+            Texture::SolidColor(color) => color.value(0.0, 0.0, Vec3::zero()).x > 0.0,
+            _ => true,
+        }
+    }
+}
+
+
+// parameters evaluated at one hit point
+struct Surface {
+    base: Vec3,
+    metallic: f64,
+    roughness: f64,
+    transmission: f64,
+    ior: f64,
+    sheen: f64,
+    clearcoat: f64,
+    clearcoat_gloss: f64,
+    // reflectance at normal incidence of the dielectric part and overall
+    f0_dielectric: Vec3,
+    f0: Vec3,
+}
+
+
+impl Principled {
+
+    fn surface(&self, result: &IntersectResult) -> Surface {
+        let (u, v, p) = (result.u, result.v, result.point);
+        let scalar = |t: &Texture| t.value(u, v, p).x.clamp(0.0, 1.0);
+
+        let base = self.base_color.value(u, v, p);
+        let metallic = scalar(&self.metallic);
+
+        let white = Vec3::new(1.0, 1.0, 1.0);
+        let tint = match luminance(base) > 0.0 {
+            true => base / luminance(base),
+            false => white,
+        };
+        let specular_tint = scalar(&self.specular_tint);
+        let f0_dielectric = 0.08 * scalar(&self.specular)
+            * ((1.0 - specular_tint) * white + specular_tint * tint);
+
+        Surface {
+            base,
+            metallic,
+            roughness: scalar(&self.roughness),
+            transmission: scalar(&self.transmission),
+            ior: self.ior.value(u, v, p).x.max(1.0),
+            sheen: scalar(&self.sheen),
+            clearcoat: scalar(&self.clearcoat),
+            clearcoat_gloss: scalar(&self.clearcoat_gloss),
+            f0_dielectric,
+            f0: (1.0 - metallic) * f0_dielectric + metallic * base,
+        }
+    }
+}
+
+
+impl Surface {
+
+    fn coat(&self) -> Ggx {
+        Ggx { alpha: lerp(0.1, 0.001, self.clearcoat_gloss) }
+    }
+
+    // share of the light reflected by the clearcoat at the macro surface
+    fn coat_chance(&self, wo: Vec3) -> f64 {
+        self.clearcoat * fresnel_dielectric(wo.z, 1.0 / CLEARCOAT_IOR)
+    }
+
+    // diffuse and sheen brdf times pi, before the dielectric share
+    fn diffuse(&self, wo: Vec3, wi: Vec3) -> Vec3 {
+        let cos_d = wi.dot((wo + wi).unit());
+        let fd90 = 0.5 + 2.0 * self.roughness * cos_d * cos_d;
+        let retro = (1.0 + (fd90 - 1.0) * schlick_weight(wi.z))
+            * (1.0 + (fd90 - 1.0) * schlick_weight(wo.z));
+
+        // the diffuse base only sees light the specular layer let through
+        let white = Vec3::new(1.0, 1.0, 1.0);
+        let diffuse = self.base * retro * (white - fresnel_schlick(wo.z, self.f0_dielectric));
+        let sheen = PI * self.sheen * schlick_weight(cos_d);
+
+        diffuse + Vec3::new(sheen, sheen, sheen)
+    }
+}
+
+
+impl Scatter for Principled {
+
+    fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult> {
+
+        let s = self.surface(&result);
+
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        if wo.z <= 0.0 { return None }
+
+        let distribution = Ggx::from_roughness(s.roughness);
+        let mut rng = rand::thread_rng();
+
+        let reflected = |wi: Vec3, attenuation: Vec3, specular: bool| Some(ScatterResult {
+            ray: Ray::new(result.point + (crate::BIAS * result.normal), frame.to_world(wi)),
+            attenuation,
+            specular,
+        });
+        let transmitted = |wi: Vec3, attenuation: Vec3| Some(ScatterResult {
+            ray: Ray::new(result.point - (crate::BIAS * result.normal), frame.to_world(wi)),
+            attenuation,
+            specular: true,
+        });
+
+        // leaving a transmissive object only the glass interface is left,
+        // reflect or refract with probability given by fresnel
+        if !result.front_face && s.transmission > 0.0 {
+            let m = sample_normal(&distribution, wo, &mut rng);
+            let should_reflect = fresnel_dielectric(wo.dot(m), s.ior) > rng.gen::<f64>();
+            let wi = match should_reflect {
+                true => Vec3::reflect(-wo, m),
+                false => Vec3::refract(-wo, m, s.ior).unit(),
+            };
+            if should_reflect != (wi.z > 0.0) { return None }
+
+            let weight = masking(&distribution, wo, wi);
+            let attenuation = Vec3::new(weight, weight, weight);
+            return match should_reflect {
+                true => reflected(wi, attenuation, true),
+                false => transmitted(wi, attenuation),
+            }
+        }
+
+        // lobe selection. the clearcoat is picked with the probability of
+        // its own fresnel reflectance, which cancels the energy it takes
+        // away from the layers below. the base lobes are picked roughly in
+        // proportion to how much they contribute
+        let coat_chance = s.coat_chance(wo);
+
+        if rng.gen::<f64>() < coat_chance {
+            let coat = s.coat();
+
+            let m = sample_normal(&coat, wo, &mut rng);
+            let wi = Vec3::reflect(-wo, m);
+            if wi.z <= 0.0 { return None }
+
+            let f = fresnel_dielectric(wo.dot(m), 1.0 / CLEARCOAT_IOR);
+            let weight = s.clearcoat * f * masking(&coat, wo, wi) / coat_chance;
+            return reflected(wi, Vec3::new(weight, weight, weight), coat.is_sharp())
+        }
+
+        let dielectric = 1.0 - s.metallic;
+        let specular_chance = luminance(fresnel_schlick(wo.z, s.f0));
+        let diffuse_chance = dielectric * (1.0 - s.transmission);
+        let transmission_chance = dielectric * s.transmission;
+
+        let total = specular_chance + diffuse_chance + transmission_chance;
+        if total <= 0.0 { return None }
+
+        let pick = rng.gen::<f64>() * total;
+
+        if pick < specular_chance {
+            let m = sample_normal(&distribution, wo, &mut rng);
+            let wi = Vec3::reflect(-wo, m);
+            if wi.z <= 0.0 { return None }
+
+            let weight = masking(&distribution, wo, wi) * total / specular_chance;
+            return reflected(wi, fresnel_schlick(wo.dot(m), s.f0) * weight, distribution.is_sharp())
+        }
+
+        if pick < specular_chance + diffuse_chance {
+            // cosine weighted, f * cos / pdf is the brdf times pi
+            let wi = (Vec3::new(0.0, 0.0, 1.0) + Vec3::random_in_unit_sphere().unit()).unit();
+            if wi.z <= 0.0 || wi.z.is_nan() { return None }
+
+            // the lobe weight equals its chance, only the total is left
+            return reflected(wi, s.diffuse(wo, wi) * total, false)
+        }
+
+        // rough refraction into the object, colored by the base color
+        let m = sample_normal(&distribution, wo, &mut rng);
+        let f = fresnel_dielectric(wo.dot(m), 1.0 / s.ior);
+        if f >= 1.0 { return None }
+
+        let wi = Vec3::refract(-wo, m, 1.0 / s.ior).unit();
+        if wi.z >= 0.0 { return None }
+
+        let weight = (1.0 - f) * masking(&distribution, wo, wi) * total;
+        transmitted(wi, s.base * weight)
+    }
+
+    // reflection lobes only, light refracted into the object is not sampled
+    fn eval(&self, ray: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        let s = self.surface(result);
+        if !result.front_face && s.transmission > 0.0 { return Vec3::zero() }
+
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        let wi = frame.to_local(wi);
+        if wo.z <= 0.0 || wi.z <= 0.0 { return Vec3::zero() }
+
+        let h = (wo + wi).unit();
+        let distribution = Ggx::from_roughness(s.roughness);
+
+        let coat = s.clearcoat * fresnel_dielectric(wo.dot(h), 1.0 / CLEARCOAT_IOR)
+            * s.coat().reflection(wo, wi);
+        let specular = fresnel_schlick(wo.dot(h), s.f0) * distribution.reflection(wo, wi);
+        let diffuse = s.diffuse(wo, wi)
+            * ((1.0 - s.metallic) * (1.0 - s.transmission) * wi.z / PI);
+
+        Vec3::new(coat, coat, coat) + (1.0 - s.coat_chance(wo)) * (specular + diffuse)
+    }
+
+    fn emit(&self, result: &IntersectResult) -> Vec3 {
+        self.emission.value(result.u, result.v, result.point) * self.emission_strength
+    }
+
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.normal_map.as_ref()
+    }
+
+}
+
+
+fn sample_normal(distribution: &Ggx, wo: Vec3, rng: &mut impl Rng) -> Vec3 {
+    match distribution.is_smooth() {
+        true => Vec3::new(0.0, 0.0, 1.0),
+        false => distribution.sample_visible_normal(wo, rng.gen(), rng.gen()),
+    }
+}
+
+// G2 / G1, the weight left after sampling visible normals
+fn masking(distribution: &Ggx, wo: Vec3, wi: Vec3) -> f64 {
+    match distribution.is_smooth() {
+        true => 1.0,
+        false => distribution.g2(wo, wi) / distribution.g1(wo),
+    }
+}
+
+fn schlick_weight(cos: f64) -> f64 {
+    (1.0 - cos.clamp(0.0, 1.0)).powi(5)
+}
@@ -0,0 +1,132 @@
+
+use crate::Vec3;
+use crate::Ray;
+use crate::Color;
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult};
+
+use crate::materials::Material;
+use crate::materials::microfacet::{ Frame, Ggx, fresnel_dielectric };
+use crate::textures::NormalMap;
+
+use rand::Rng;
+
+
+// ggx microfacet transmission (Walter et al. 2007) for frosted glass
+#[derive(Debug, Clone)]
+pub struct RoughDielectric {
+    refraction_index: f64,
+    distribution: Ggx,
+    // filter applied once when light enters the object
+    tint: Vec3,
+    pub normal_map: Option<NormalMap>,
+}
+
+
+impl RoughDielectric {
+
+    pub fn new(ir: f64, roughness: f64) -> Material {
+        Self {
+            refraction_index: ir,
+            distribution: Ggx::from_roughness(roughness),
+            tint: Vec3::new(1.0, 1.0, 1.0),
+            normal_map: None,
+        }.into()
+    }
+
+    // bare interface for materials that put it around something else
+    pub fn interface(ir: f64, roughness: f64) -> Self {
+        Self {
+            refraction_index: ir,
+            distribution: Ggx::from_roughness(roughness),
+            tint: Vec3::new(1.0, 1.0, 1.0),
+            normal_map: None,
+        }
+    }
+
+    pub fn tinted(ir: f64, roughness: f64, color: Color) -> Material {
+        Self {
+            refraction_index: ir,
+            distribution: Ggx::from_roughness(roughness),
+            tint: color.normalize(),
+            normal_map: None,
+        }.into()
+    }
+}
+
+
+impl Scatter for RoughDielectric {
+
+    fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult> {
+
+        let refraction_ratio = match result.front_face {
+            true => 1.0 / self.refraction_index,
+            false => self.refraction_index,
+        };
+
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        if wo.z <= 0.0 { return None }
+
+        let mut rng = rand::thread_rng();
+        let m = match self.distribution.is_smooth() {
+            true => Vec3::new(0.0, 0.0, 1.0),
+            false => self.distribution.sample_visible_normal(wo, rng.gen(), rng.gen()),
+        };
+
+        // picking reflection with probability F cancels the fresnel term,
+        // leaving G2 / G1 as the weight of either lobe
+        let cos_theta = wo.dot(m);
+        let should_reflect = fresnel_dielectric(cos_theta, refraction_ratio) > rng.gen::<f64>();
+
+        let wi = match should_reflect {
+            true => Vec3::reflect(-wo, m),
+            false => Vec3::refract(-wo, m, refraction_ratio).unit(),
+        };
+
+        // the microfacet sent the ray to the wrong side of the macro surface
+        if should_reflect != (wi.z > 0.0) { return None }
+
+        let weight = match self.distribution.is_smooth() {
+            true => 1.0,
+            false => self.distribution.g2(wo, wi) / self.distribution.g1(wo),
+        };
+
+        let attenuation = match !should_reflect && result.front_face {
+            true => self.tint * weight,
+            false => Vec3::new(weight, weight, weight),
+        };
+
+        let origin = match should_reflect {
+            true => result.point + (crate::BIAS * result.normal),
+            false => result.point - (crate::BIAS * result.normal),
+        };
+
+        Some(ScatterResult {
+            ray: Ray::new(origin, frame.to_world(wi)),
+            attenuation,
+            specular: !should_reflect || self.distribution.is_sharp(),
+        })
+    }
+
+    // only the reflection, refracted light from lights is not sampled
+    fn eval(&self, ray: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        let refraction_ratio = match result.front_face {
+            true => 1.0 / self.refraction_index,
+            false => self.refraction_index,
+        };
+
+        let frame = Frame::new(result.normal);
+        let wo = frame.to_local(-ray.direction().unit());
+        let wi = frame.to_local(wi);
+
+        let h = (wo + wi).unit();
+        let f = fresnel_dielectric(wo.dot(h), refraction_ratio)
+            * self.distribution.reflection(wo, wi);
+        Vec3::new(f, f, f)
+    }
+
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.normal_map.as_ref()
+    }
+
+}
@@ -0,0 +1,88 @@
+use crate::Vec3;
+use crate::Ray;
+use crate::Color;
+use crate::behaviors::{Scatter, ScatterResult, IntersectResult};
+
+use crate::materials::{ Material, RoughDielectric };
+use crate::media::{ Medium, Homogeneous, Phase };
+use crate::textures::NormalMap;
+
+
+// random walk subsurface scattering for wax, jade, skin or marble. the
+// surface is a dielectric interface and the inside a dense scattering
+// medium, the walk itself is done by the integrator like any other medium
+// and finds its exit points through the regular scene intersection. only
+// makes sense on closed objects
+#[derive(Debug, Clone)]
+pub struct Subsurface {
+    pub surface: RoughDielectric,
+    interior: Medium,
+}
+
+
+impl Subsurface {
+
+    // color is the overall diffuse look, radius the mean free path per
+    // channel in world units, how far each color bleeds into the object
+    pub fn new(color: Color, radius: Vec3, ir: f64, roughness: f64) -> Material {
+        let color = color.normalize();
+
+        let sigma_t = Vec3::new(1.0 / radius.x, 1.0 / radius.y, 1.0 / radius.z);
+        let albedo = Vec3::new(
+            single_scatter_albedo(color.x),
+            single_scatter_albedo(color.y),
+            single_scatter_albedo(color.z),
+        );
+        let sigma_s = albedo * sigma_t;
+
+        Self {
+            surface: RoughDielectric::interface(ir, roughness),
+            interior: Homogeneous::coefficients(
+                sigma_s, sigma_t - sigma_s, Phase::Isotropic
+            ),
+        }.into()
+    }
+
+    pub fn jade() -> Material {
+        Self::new(Color::rgb(70, 160, 90), Vec3::new(0.6, 1.2, 0.7), 1.6, 0.15)
+    }
+
+    pub fn wax() -> Material {
+        Self::new(Color::rgb(240, 220, 170), Vec3::new(0.5, 0.35, 0.2), 1.45, 0.3)
+    }
+
+    pub fn marble() -> Material {
+        Self::new(Color::rgb(235, 232, 225), Vec3::new(0.25, 0.22, 0.2), 1.5, 0.05)
+    }
+}
+
+
+// albedo of a single scattering event that gives roughly the requested
+// multiple scattering albedo after a random walk (van de Hulst, as fitted
+// for random walk sss in production renderers)
+fn single_scatter_albedo(multiple: f64) -> f64 {
+    let a = multiple.clamp(0.0, 0.999);
+    let s = 4.09712 + 4.20863 * a - (9.59217 + 41.6808 * a + 17.7126 * a * a).sqrt();
+    (1.0 - s * s).clamp(0.0, 1.0)
+}
+
+
+impl Scatter for Subsurface {
+
+    fn scatter(&self, ray: &Ray, result: IntersectResult) -> Option<ScatterResult> {
+        self.surface.scatter(ray, result)
+    }
+
+    fn eval(&self, ray: &Ray, result: &IntersectResult, wi: Vec3) -> Vec3 {
+        self.surface.eval(ray, result, wi)
+    }
+
+    fn normal_map(&self) -> Option<&NormalMap> {
+        self.surface.normal_map.as_ref()
+    }
+
+    fn interior(&self) -> Option<&Medium> {
+        Some(&self.interior)
+    }
+
+}
@@ -0,0 +1,24 @@
+use enum_dispatch::enum_dispatch;
+
+
+pub mod phase;
+pub mod homogeneous;
+pub mod heterogeneous;
+
+pub use phase::Phase;
+pub use homogeneous::Homogeneous;
+pub use heterogeneous::Heterogeneous;
+
+
+// for enum dispatch
+use crate::Ray;
+use crate::Vec3;
+use crate::behaviors::{ Participate, MediumSample };
+
+
+#[enum_dispatch]
+#[derive(Debug, Clone)]
+pub enum Medium {
+    Homogeneous,
+    Heterogeneous,
+}
@@ -0,0 +1,96 @@
+use crate::Vec3;
+use crate::Ray;
+use crate::Color;
+use crate::behaviors::{ Participate, MediumSample };
+
+use crate::media::{ Medium, Phase };
+
+use rand::Rng;
+
+
+// medium with the same density everywhere, fog, smoke or murky water
+#[derive(Debug, Clone)]
+pub struct Homogeneous {
+    // scattering and absorption coefficients per unit distance
+    sigma_s: Vec3,
+    sigma_a: Vec3,
+    phase: Phase,
+}
+
+
+impl Homogeneous {
+
+    // density is the extinction per unit distance, color the share of it
+    // that scatters instead of being absorbed
+    pub fn new(color: Color, density: f64, phase: Phase) -> Medium {
+        let albedo = color.normalize();
+        Self {
+            sigma_s: albedo * density,
+            sigma_a: (Vec3::new(1.0, 1.0, 1.0) - albedo) * density,
+            phase,
+        }.into()
+    }
+
+    pub fn coefficients(sigma_s: Vec3, sigma_a: Vec3, phase: Phase) -> Medium {
+        Self { sigma_s, sigma_a, phase }.into()
+    }
+
+    fn sigma_t(&self) -> Vec3 {
+        self.sigma_s + self.sigma_a
+    }
+}
+
+
+impl Participate for Homogeneous {
+
+    fn sample(&self, ray: &Ray, t_max: f64) -> MediumSample {
+        // coefficients are per world unit, rays are not normalized
+        let speed = ray.direction().len();
+        let sigma_t = self.sigma_t() * speed;
+        let transmittance = |t: f64| {
+            let channel = |sigma: f64| match sigma > 0.0 {
+                true => (-sigma * t).exp(),
+                false => 1.0,
+            };
+            Vec3::new(channel(sigma_t.x), channel(sigma_t.y), channel(sigma_t.z))
+        };
+
+        // sample the distance with a randomly picked channel, the pdf is
+        // the average over all channels so colored media stay unbiased
+        let mut rng = rand::thread_rng();
+        let sigma = sigma_t[rng.gen_range(0..3)];
+        let t = match sigma > 0.0 {
+            true => -(1.0 - rng.gen::<f64>()).ln() / sigma,
+            false => f64::INFINITY,
+        };
+
+        match t < t_max {
+            true => {
+                let tr = transmittance(t);
+                let density = sigma_t * tr;
+                let pdf = (density.x + density.y + density.z) / 3.0;
+                MediumSample { t: Some(t), weight: tr * self.sigma_s * speed / pdf }
+            },
+            false => {
+                let tr = transmittance(t_max);
+                let pdf = (tr.x + tr.y + tr.z) / 3.0;
+                MediumSample { t: None, weight: tr / pdf }
+            },
+        }
+    }
+
+    fn transmittance(&self, ray: &Ray, t_max: f64) -> Vec3 {
+        let distance = t_max * ray.direction().len();
+        let sigma_t = self.sigma_t();
+        let channel = |sigma: f64| match sigma > 0.0 {
+            true => (-sigma * distance).exp(),
+            false => 1.0,
+        };
+        Vec3::new(channel(sigma_t.x), channel(sigma_t.y), channel(sigma_t.z))
+    }
+
+    fn phase(&self) -> Phase {
+        self.phase
+    }
+
+}
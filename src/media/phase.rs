@@ -0,0 +1,96 @@
+use std::f64::consts::PI;
+
+use crate::Vec3;
+
+use rand::Rng;
+
+
+// angular distribution of light scattered inside a medium
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Isotropic,
+    // asymmetry g in -1..1, positive scatters forward, negative backward
+    HenyeyGreenstein(f64),
+}
+
+
+impl Phase {
+
+    // density over the sphere of directions for light travelling along
+    // `direction` to be scattered into `scattered`
+    pub fn eval(&self, direction: Vec3, scattered: Vec3) -> f64 {
+        let isotropic = 1.0 / (4.0 * PI);
+        match *self {
+            Phase::HenyeyGreenstein(g) if g.abs() > 1e-3 => {
+                let g = g.clamp(-0.99, 0.99);
+                let cos = direction.unit().dot(scattered.unit());
+                let denom = 1.0 + g * g - 2.0 * g * cos;
+                isotropic * (1.0 - g * g) / (denom * denom.sqrt())
+            },
+            _ => isotropic,
+        }
+    }
+
+    // new direction for light travelling along `direction`, sampled exactly
+    // proportional to the phase function so the sample weight is 1
+    pub fn sample(&self, direction: Vec3) -> Vec3 {
+        let mut rng = rand::thread_rng();
+        let (u1, u2): (f64, f64) = (rng.gen(), rng.gen());
+
+        let cos_theta = match *self {
+            Phase::HenyeyGreenstein(g) if g.abs() > 1e-3 => {
+                let g = g.clamp(-0.99, 0.99);
+                let s = (1.0 - g * g) / (1.0 + g - 2.0 * g * u1);
+                (1.0 + g * g - s * s) / (2.0 * g)
+            },
+            _ => 1.0 - 2.0 * u1,
+        };
+
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * u2;
+
+        let w = direction.unit();
+        let (u, v) = w.orthonormal_basis();
+        (sin_theta * phi.cos()) * u + (sin_theta * phi.sin()) * v + cos_theta * w
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn henyey_greenstein_normalized() {
+        // integrates to 1 over the sphere
+        for g in [-0.5, 0.0, 0.7] {
+            let n = 2000;
+            let mut sum = 0.0;
+            for i in 0..n {
+                let cos = -1.0 + 2.0 * (i as f64 + 0.5) / n as f64;
+                let scattered = Vec3::new((1.0 - cos * cos).sqrt(), 0.0, cos);
+                sum += Phase::HenyeyGreenstein(g).eval(Vec3::new(0.0, 0.0, 1.0), scattered);
+            }
+            let integral = sum * 2.0 / n as f64 * 2.0 * PI;
+            assert!((integral - 1.0).abs() < 1e-3, "{} {}", g, integral);
+        }
+    }
+
+    #[test]
+    fn henyey_greenstein_mean_cosine() {
+        // the mean cosine of the scattering angle is g
+        let direction = Vec3::new(0.3, -0.5, 0.8).unit();
+        for g in [-0.6, 0.0, 0.3, 0.8] {
+            let n = 200_000;
+            let mut sum = 0.0;
+            for _ in 0..n {
+                let wi = Phase::HenyeyGreenstein(g).sample(direction);
+                assert!((wi.len() - 1.0).abs() < 1e-9);
+                sum += wi.dot(direction);
+            }
+            let mean = sum / n as f64;
+            assert!((mean - g).abs() < 0.01, "{} {}", g, mean);
+        }
+    }
+}
@@ -1,30 +1,106 @@
 pub mod sphere;
 pub mod mesh;
 pub mod triangle;
+pub mod plane;
+pub mod disk;
+pub mod quad;
+pub mod cylinder;
+pub mod cone;
+pub mod torus;
+pub mod constant_medium;
+pub mod instance;
+pub mod transformed;
+pub mod csg;
+pub mod sdf;
+pub mod curve;
+pub mod subdivision;
 // pub mod world;
 pub mod aabb;
 pub mod bvh;
+pub mod tlas;
 pub mod helpers;
 // pub mod nullobject;
 
 pub use sphere::Sphere;
 pub use mesh::Mesh;
 pub use triangle::Triangle;
+pub use plane::Plane;
+pub use disk::Disk;
+pub use quad::Quad;
+pub use cylinder::Cylinder;
+pub use cone::Cone;
+pub use torus::Torus;
+pub use constant_medium::ConstantMedium;
+pub use instance::{ Instance, Prototype, Sealed };
+pub use transformed::{ Transformed, Transform };
+pub use csg::Csg;
+pub use sdf::{ Sdf, Distance };
+pub use curve::{ Curve, CurveKind, Strand };
+pub use subdivision::Scheme;
 pub use aabb::Aabb;
 pub use bvh::BvhNode;
+pub use tlas::Tlas;
 // pub use nullobject::NullObject;
 // pub use world::World;
 
 use std::fmt;
+use std::sync::Arc;
 use crate::Ray;
+use crate::materials::Material;
 use crate::behaviors::{Intersect, IntersectResult};
 
 use enum_dispatch::enum_dispatch;
 
 #[enum_dispatch]
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum Object {
     Sphere,
     Triangle,
+    Plane,
+    Disk,
+    Quad,
+    Cylinder,
+    Cone,
+    Torus,
     Mesh,
+    ConstantMedium,
+    Instance,
+    Transformed,
+    Csg,
+    Sdf,
+    Curve,
+}
+
+
+impl Object {
+
+    // meshes of materials entered through their surface are hit from
+    // inside too, so rays refracted or walking in them find the way out.
+    // `material` is the override of the instance the object is placed by,
+    // returns whether anything had to be sealed
+    pub fn seal(
+        &mut self, materials: &[Material], material: Option<usize>, sealed: &mut Sealed
+    ) -> bool {
+        match self {
+            Object::Mesh(mesh) => {
+                let material = &materials[material.unwrap_or(mesh.material)];
+                let inside = !mesh.double_sided && material.transmissive(materials, 0);
+                mesh.double_sided |= inside;
+                inside
+            },
+            Object::Transformed(transformed) => {
+                transformed.object.seal(materials, material, sealed)
+            },
+            Object::Instance(instance) => {
+                let prototype = Prototype::sealed(
+                    &instance.prototype, materials, material.or(instance.material), sealed
+                );
+                let changed = !Arc::ptr_eq(&prototype, &instance.prototype);
+                instance.prototype = prototype;
+                changed
+            },
+            _ => false,
+        }
+    }
 }
@@ -4,7 +4,7 @@ use crate::behaviors::{ Intersect, IntersectResult };
 use crate::objects::{ Aabb, Object };
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BvhNode {
     pub object: Option<usize>,
     pub left: Option<usize>,
@@ -0,0 +1,210 @@
+use std::fmt;
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+use crate::objects::disk::{ disk_uv, disk_bbox };
+use crate::materials::microfacet::Frame;
+
+use rand::Rng;
+
+
+// cone on a capped base disk around `base`, its apex `height` along `axis`.
+// on the side u goes around and v up to the apex, the cap is mapped like
+// a disk
+#[derive(Debug, Clone)]
+pub struct Cone {
+    pub base: Vec3,
+    pub axis: Vec3,
+    pub radius: f64,
+    pub height: f64,
+    frame: Frame,
+    pub material: usize,
+}
+
+
+impl Cone {
+
+    pub fn new(
+        base: Vec3, axis: Vec3, radius: f64, height: f64, material: usize
+    ) -> Object {
+        let axis = axis.unit();
+        Self { base, axis, radius, height, frame: Frame::new(axis), material }.into()
+    }
+
+    fn slant(&self) -> f64 {
+        (self.radius * self.radius + self.height * self.height).sqrt()
+    }
+
+    pub fn area(&self) -> f64 {
+        PI * self.radius * (self.radius + self.slant())
+    }
+
+    // point and normal picked uniformly by area, side or cap. the side
+    // widens linearly towards the base, so the distance from the apex
+    // goes with the square root
+    pub fn sample(&self) -> (Vec3, Vec3) {
+        let mut rng = rand::thread_rng();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let (sin, cos) = phi.sin_cos();
+        let rho = self.radius * rng.gen::<f64>().sqrt();
+
+        let side = self.slant() / (self.radius + self.slant());
+        let (local, normal) = match rng.gen::<f64>() < side {
+            true => {
+                let z = self.height * (1.0 - rho / self.radius);
+                let normal = Vec3::new(self.height * cos, self.height * sin, self.radius);
+                (Vec3::new(rho * cos, rho * sin, z), normal.unit())
+            },
+            false => (Vec3::new(rho * cos, rho * sin, 0.0), Vec3::new(0.0, 0.0, -1.0)),
+        };
+
+        (self.base + self.frame.to_world(local), self.frame.to_world(normal))
+    }
+}
+
+
+impl Intersect for Cone {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let o = self.frame.to_local(ray.origin() - self.base);
+        let d = self.frame.to_local(ray.direction());
+        let (r, h) = (self.radius, self.height);
+        let k = r / h;
+
+        let mut nearest: Option<(f64, Vec3)> = None;
+        let mut consider = |t: f64, normal: Vec3| {
+            if t >= t_min && t <= t_max && nearest.is_none_or(|(best, _)| t < best) {
+                nearest = Some((t, normal));
+            }
+        };
+
+        // side, x^2 + y^2 = (k (h - z))^2 between base and apex. rays
+        // parallel to the slant leave a linear equation
+        let a = d.x * d.x + d.y * d.y - k * k * d.z * d.z;
+        let half_b = o.x * d.x + o.y * d.y + k * k * (h - o.z) * d.z;
+        let c = o.x * o.x + o.y * o.y - k * k * (h - o.z) * (h - o.z);
+        let roots = match a.abs() > 1e-12 {
+            true => {
+                let discriminant = half_b * half_b - a * c;
+                match discriminant >= 0.0 {
+                    true => {
+                        let sqrtd = discriminant.sqrt();
+                        vec![(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+                    },
+                    false => vec![],
+                }
+            },
+            false if half_b.abs() > 1e-12 => vec![-c / (2.0 * half_b)],
+            false => vec![],
+        };
+        for t in roots {
+            let p = o + t * d;
+            if p.z >= 0.0 && p.z <= h {
+                // gradient of the implicit surface, up at the apex
+                let rho = (p.x * p.x + p.y * p.y).sqrt();
+                let normal = match rho > 1e-12 {
+                    true => Vec3::new(p.x, p.y, k * rho).unit(),
+                    false => Vec3::new(0.0, 0.0, 1.0),
+                };
+                consider(t, normal);
+            }
+        }
+
+        // base cap
+        if d.z.abs() > 1e-12 {
+            let t = -o.z / d.z;
+            let p = o + t * d;
+            if p.x * p.x + p.y * p.y <= r * r {
+                consider(t, Vec3::new(0.0, 0.0, -1.0));
+            }
+        }
+
+        let (t, normal) = nearest?;
+        let p = o + t * d;
+        let (u, v, dpdu, dpdv) = match normal.z >= 0.0 {
+            true => {
+                let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+                let v = p.z / h;
+                let dpdu = 2.0 * PI * Vec3::new(-p.y, p.x, 0.0);
+                let dpdv = match v < 1.0 {
+                    true => Vec3::new(-p.x, -p.y, h - p.z) / (1.0 - v),
+                    false => Vec3::zero(),
+                };
+                (phi / (2.0 * PI), v, dpdu, dpdv)
+            },
+            false => disk_uv(p, r),
+        };
+
+        Some(
+            IntersectResult::new(ray, t, self.frame.to_world(normal), self.material)
+                .with_uv(u, v)
+                .with_tangents(self.frame.to_world(dpdu), self.frame.to_world(dpdv))
+                .sampled()
+        )
+    }
+
+    fn bbox(&self) -> Aabb {
+        let apex = self.base + self.height * self.axis;
+        disk_bbox(self.base, self.axis, self.radius)
+            .merge(Aabb { lower: apex, upper: apex })
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "<Cone [ base: {:?}, axis: {:?}, radius: {}, height: {} ]>",
+            self.base, self.axis, self.radius, self.height
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cone_hits() {
+        let cone = Cone::new(Vec3::zero(), Vec3::new(0.0, 1.0, 0.0), 1.0, 2.0, 0);
+        let hit = |origin: Vec3, direction: Vec3| {
+            cone.intersect(&Ray::new(origin, direction), 0.001, f64::INFINITY)
+        };
+
+        // side halfway up where the radius is 0.5, the normal leans up
+        let result = hit(Vec3::new(5.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((result.t - 4.5).abs() < 1e-12);
+        let expected = Vec3::new(2.0, 1.0, 0.0).unit();
+        assert!((result.normal - expected).len() < 1e-12);
+
+        // straight down onto the apex, up into the base cap and from inside
+        let result = hit(Vec3::new(0.0, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((result.t - 3.0).abs() < 1e-12);
+        let result = hit(Vec3::new(0.2, -3.0, 0.0), Vec3::new(0.0, 1.0, 0.0)).unwrap();
+        assert!((result.t - 3.0).abs() < 1e-12);
+        assert!((result.normal - Vec3::new(0.0, -1.0, 0.0)).len() < 1e-12);
+        let result = hit(Vec3::new(0.0, 0.5, 0.0), Vec3::new(1.0, 0.0, 0.0)).unwrap();
+        assert!((result.t - 0.75).abs() < 1e-12);
+        assert!(!result.front_face);
+
+        // parallel to the slant, hits the side once
+        let slant = Vec3::new(1.0, -2.0, 0.0);
+        let result = hit(Vec3::new(-1.0, 2.0, 0.0), slant).unwrap();
+        assert!((result.point - Vec3::new(-0.5, 1.0, 0.0)).len() < 1e-9);
+
+        // grazing the apex from the side and missing above it
+        assert!(hit(Vec3::new(5.0, 2.0001, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+        // the mirrored cone above the apex is not part of it
+        assert!(hit(Vec3::new(5.0, 3.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+
+        let bbox = cone.bbox();
+        assert!((bbox.upper - Vec3::new(1.0, 2.0, 1.0)).len() < 0.001);
+    }
+}
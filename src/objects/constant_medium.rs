@@ -0,0 +1,68 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+use crate::media::Medium;
+
+
+// fills a closed boundary shape with a participating medium. the boundary
+// surface itself is invisible, crossing it switches the medium the ray is
+// travelling through, the material of the boundary is ignored. the medium
+// is shared by the pieces of a divided boundary and handed to every hit
+#[derive(Debug, Clone)]
+pub struct ConstantMedium {
+    pub boundary: Box<Object>,
+    pub medium: Arc<Medium>,
+}
+
+
+impl ConstantMedium {
+
+    pub fn new(boundary: Object, medium: Medium) -> Object {
+        Self::shared(boundary, Arc::new(medium))
+    }
+
+    fn shared(mut boundary: Object, medium: Arc<Medium>) -> Object {
+        // rays inside have to find their way out through the back faces
+        if let Object::Mesh(mesh) = &mut boundary {
+            mesh.double_sided = true;
+        }
+        Self { boundary: Box::new(boundary), medium }.into()
+    }
+}
+
+
+impl Intersect for ConstantMedium {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        self.boundary
+            .intersect(ray, t_min, t_max)
+            .map(|result| result.with_medium(Arc::clone(&self.medium)))
+    }
+
+    fn bbox(&self) -> Aabb {
+        self.boundary.bbox()
+    }
+
+    // meshes are split up like any other mesh, every triangle keeps
+    // pointing to the same medium
+    fn divide(&self) -> Option<Vec<Object>> {
+        self.boundary.divide().map(|pieces| {
+            pieces
+                .into_iter()
+                .map(|piece| ConstantMedium::shared(piece, Arc::clone(&self.medium)))
+                .collect()
+        })
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<ConstantMedium [ boundary: ")?;
+        self.boundary.repr(f)?;
+        write!(f, ", medium: {:?} ]>", self.medium)
+    }
+
+}
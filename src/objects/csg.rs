@@ -0,0 +1,222 @@
+use std::fmt;
+
+use crate::{ Ray, Matrix4 };
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object, Instance, Prototype };
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOp {
+    Union,
+    Intersection,
+    // the first object with the second cut out of it
+    Difference,
+}
+
+
+impl CsgOp {
+
+    // whether a point inside or outside of either object is in the result
+    fn contains(&self, [a, b]: [bool; 2]) -> bool {
+        match self {
+            CsgOp::Union => a || b,
+            CsgOp::Intersection => a && b,
+            CsgOp::Difference => a && !b,
+        }
+    }
+}
+
+
+// boolean combination of two closed objects. the hits of both along a ray
+// split it into spans inside and outside of each, the result keeps the
+// hits where the ray goes in or out of the combination. every surface
+// keeps the material of the object it belongs to
+#[derive(Debug, Clone)]
+pub struct Csg {
+    pub operation: CsgOp,
+    pub a: Box<Object>,
+    pub b: Box<Object>,
+}
+
+
+impl Csg {
+
+    pub fn new(operation: CsgOp, a: Object, b: Object) -> Object {
+        Self { operation, a: Box::new(closed(a)), b: Box::new(closed(b)) }.into()
+    }
+
+    pub fn union(a: Object, b: Object) -> Object {
+        Self::new(CsgOp::Union, a, b)
+    }
+
+    pub fn intersection(a: Object, b: Object) -> Object {
+        Self::new(CsgOp::Intersection, a, b)
+    }
+
+    pub fn difference(a: Object, b: Object) -> Object {
+        Self::new(CsgOp::Difference, a, b)
+    }
+}
+
+
+// meshes can only be hit once divided, they get a bvh of their own. rays
+// inside have to find their way out through the back faces
+fn closed(mut object: Object) -> Object {
+    match &mut object {
+        Object::Mesh(mesh) => mesh.double_sided = true,
+        Object::Transformed(transformed) => {
+            if let Object::Mesh(mesh) = &mut *transformed.object {
+                mesh.double_sided = true;
+            }
+        },
+        _ => (),
+    }
+
+    match object.divide() {
+        Some(pieces) => Instance::new(&Prototype::new(pieces), Matrix4::identity()),
+        None => object,
+    }
+}
+
+
+impl Intersect for Csg {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        self.intersect_all(ray, t_min, t_max).into_iter().next()
+    }
+
+    fn intersect_all(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<IntersectResult> {
+        // hits past t_max still tell whether the ray starts inside
+        let a = self.a.intersect_all(ray, t_min, f64::INFINITY);
+        let b = self.b.intersect_all(ray, t_min, f64::INFINITY);
+
+        // a ray that leaves an object first started inside of it
+        let starts_inside = |hits: &[IntersectResult]| {
+            hits.first().is_some_and(|hit| !hit.front_face)
+        };
+        let mut inside = [starts_inside(&a), starts_inside(&b)];
+        let mut was_inside = self.operation.contains(inside);
+
+        let mut events: Vec<(usize, IntersectResult)> = a
+            .into_iter()
+            .map(|hit| (0, hit))
+            .chain(b.into_iter().map(|hit| (1, hit)))
+            .collect();
+        events.sort_by(|(_, x), (_, y)| x.t.total_cmp(&y.t));
+
+        let mut hits = vec![];
+        for (child, mut hit) in events {
+            if hit.t > t_max { break }
+
+            inside[child] = hit.front_face;
+            let now_inside = self.operation.contains(inside);
+            if now_inside == was_inside { continue }
+            was_inside = now_inside;
+
+            // going into the cut out object is leaving the result, the
+            // normal facing the ray stays the same
+            if self.operation == CsgOp::Difference && child == 1 {
+                hit.front_face = !hit.front_face;
+            }
+            // only the parts are shapes lights are made of, not the result
+            hit.sampled = false;
+            hits.push(hit);
+        }
+        hits
+    }
+
+    fn bbox(&self) -> Aabb {
+        let (a, b) = (self.a.bbox(), self.b.bbox());
+        match self.operation {
+            CsgOp::Union => a.merge(b),
+            CsgOp::Intersection => Aabb {
+                lower: crate::Vec3::new(
+                    a.lower.x.max(b.lower.x), a.lower.y.max(b.lower.y), a.lower.z.max(b.lower.z)
+                ),
+                upper: crate::Vec3::new(
+                    a.upper.x.min(b.upper.x), a.upper.y.min(b.upper.y), a.upper.z.min(b.upper.z)
+                ),
+            },
+            CsgOp::Difference => a,
+        }
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Csg [ operation: {:?}, a: ", self.operation)?;
+        self.a.repr(f)?;
+        write!(f, ", b: ")?;
+        self.b.repr(f)?;
+        write!(f, " ]>")
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Vec3;
+    use crate::objects::{ Sphere, helpers };
+
+    fn sphere(x: f64, radius: f64) -> Object {
+        Sphere { center: Vec3::new(x, 0.0, 0.0), radius, material: 0 }.into()
+    }
+
+    // t and front face of every hit along the x axis from x = -10
+    fn hits(object: &Object) -> Vec<(f64, bool)> {
+        let ray = Ray::new(Vec3::new(-10.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        object
+            .intersect_all(&ray, 0.001, f64::INFINITY)
+            .into_iter()
+            .map(|hit| ((hit.t * 1e6).round() / 1e6, hit.front_face))
+            .collect()
+    }
+
+    #[test]
+    fn csg_spans() {
+        // two unit spheres overlapping between x = 0 and x = 1
+        let union = Csg::union(sphere(0.0, 1.0), sphere(1.0, 1.0));
+        assert_eq!(hits(&union), vec![(9.0, true), (12.0, false)]);
+
+        let intersection = Csg::intersection(sphere(0.0, 1.0), sphere(1.0, 1.0));
+        assert_eq!(hits(&intersection), vec![(10.0, true), (11.0, false)]);
+
+        let difference = Csg::difference(sphere(0.0, 1.0), sphere(1.0, 1.0));
+        assert_eq!(hits(&difference), vec![(9.0, true), (10.0, false)]);
+
+        // hollow shell, the inner surface faces into the hollow
+        let shell = Csg::difference(sphere(0.0, 2.0), sphere(0.0, 1.0));
+        assert_eq!(
+            hits(&shell),
+            vec![(8.0, true), (9.0, false), (11.0, true), (12.0, false)]
+        );
+        let ray = Ray::new(Vec3::new(-10.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let inner = &shell.intersect_all(&ray, 0.001, f64::INFINITY)[1];
+        assert!((inner.normal - Vec3::new(-1.0, 0.0, 0.0)).len() < 1e-9);
+
+        // starting in the hollow, and stopping short of the far side
+        let ray = Ray::new(Vec3::zero(), Vec3::new(1.0, 0.0, 0.0));
+        let hit = shell.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 1.0).abs() < 1e-9 && hit.front_face);
+        assert!(shell.intersect(&ray, 0.001, 0.5).is_none());
+    }
+
+    #[test]
+    fn csg_mesh() {
+        // cube from -1 to 1 with a sphere scooped out of its +x face
+        let cube = helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0);
+        let cut = Csg::difference(cube.into(), sphere(1.0, 0.5));
+        assert_eq!(hits(&cut), vec![(9.0, true), (10.5, false)]);
+
+        // past the scoop the ray leaves through the back face of the cube
+        let ray = Ray::new(Vec3::new(-10.0, 0.8, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let far = cut.intersect_all(&ray, 0.001, f64::INFINITY);
+        assert_eq!(far.len(), 2);
+        assert!((far[1].t - 11.0).abs() < 1e-9 && !far[1].front_face);
+    }
+}
@@ -0,0 +1,439 @@
+use std::fs;
+use std::fmt;
+use std::path::Path;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+use crate::materials::microfacet::Frame;
+
+
+// most times a whole curve is split into pieces for the bvh, as a power
+// of two
+const MAX_SPLITS: usize = 3;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveKind {
+    // flat band always turned towards the ray, cheap and fine for hair
+    // seen from afar
+    Flat,
+    // the same band shaded as if it was round
+    Cylinder,
+}
+
+
+// cubic bezier through its first and last control points, its width goes
+// linearly from the first to the second. rays are tested by taking the
+// curve into a space where the ray runs down the z axis and halving it
+// until the pieces are close enough to straight lines. u runs along the
+// curve and v across it
+#[derive(Debug, Clone)]
+pub struct Curve {
+    pub points: [Vec3; 4],
+    pub width: [f64; 2],
+    pub kind: CurveKind,
+    pub material: usize,
+    // part of the whole curve the points span, pieces of a divided curve
+    // keep their place on it
+    u: [f64; 2],
+    // halvings until a piece is flat enough
+    depth: usize,
+}
+
+
+// closest crossing of a ray with a curve, in the space of the ray
+struct CurveHit {
+    z: f64,
+    u: f64,
+    v: f64,
+    // from the center of the curve to the hit and along the curve
+    side: Vec3,
+    tangent: Vec3,
+}
+
+
+impl Curve {
+
+    pub fn new(points: [Vec3; 4], width: [f64; 2], kind: CurveKind, material: usize) -> Object {
+        Self::piece(points, width, kind, material, [0.0, 1.0]).into()
+    }
+
+    fn piece(
+        points: [Vec3; 4], width: [f64; 2], kind: CurveKind, material: usize, u: [f64; 2]
+    ) -> Self {
+        // enough halvings to bring the curve within a twentieth of its
+        // width of straight lines, from how far the control points bend
+        let bend = (0..2)
+            .map(|i| {
+                let d = points[i] - 2.0 * points[i + 1] + points[i + 2];
+                d.x.abs().max(d.y.abs()).max(d.z.abs())
+            })
+            .fold(0.0, f64::max);
+        let widest = lerp(u[0], width[0], width[1]).max(lerp(u[1], width[0], width[1]));
+        let depth = match bend > 0.0 && widest > 0.0 {
+            true => {
+                let r = (2.0_f64.sqrt() * 6.0 * bend / (8.0 * 0.05 * widest)).log2() / 2.0;
+                r.round().clamp(0.0, 10.0) as usize
+            },
+            false => 0,
+        };
+
+        Self { points, width, kind, material, u, depth }
+    }
+
+    fn width_at(&self, u: f64) -> f64 {
+        lerp(u, self.width[0], self.width[1])
+    }
+
+    fn recurse(
+        &self, cp: &[Vec3; 4], u: [f64; 2], depth: usize, z_min: f64, z_max: f64
+    ) -> Option<CurveHit> {
+        // control points bound the piece, grown by half its width
+        let half = self.width_at(u[0]).max(self.width_at(u[1])) / 2.0;
+        let lower = |i: usize| cp.iter().map(|p| p[i]).fold(f64::INFINITY, f64::min) - half;
+        let upper = |i: usize| cp.iter().map(|p| p[i]).fold(f64::NEG_INFINITY, f64::max) + half;
+        if lower(0) > 0.0 || upper(0) < 0.0 || lower(1) > 0.0 || upper(1) < 0.0 {
+            return None
+        }
+        if lower(2) > z_max || upper(2) < z_min {
+            return None
+        }
+
+        if depth > 0 {
+            let [p0, p1, p2, p3, p4, p5, p6] = split(cp);
+            let middle = (u[0] + u[1]) / 2.0;
+            let first = self.recurse(&[p0, p1, p2, p3], [u[0], middle], depth - 1, z_min, z_max);
+            let z_max = first.as_ref().map_or(z_max, |hit| hit.z);
+            let second = self.recurse(&[p3, p4, p5, p6], [middle, u[1]], depth - 1, z_min, z_max);
+            return second.or(first)
+        }
+
+        // the ray passes the piece between the lines through its ends
+        // square to it
+        let edge = (cp[1].y - cp[0].y) * -cp[0].y + cp[0].x * (cp[0].x - cp[1].x);
+        if edge < 0.0 { return None }
+        let edge = (cp[2].y - cp[3].y) * -cp[3].y + cp[3].x * (cp[3].x - cp[2].x);
+        if edge < 0.0 { return None }
+
+        // closest point of the piece taken as a straight line
+        let segment = Vec3::new(cp[3].x - cp[0].x, cp[3].y - cp[0].y, 0.0);
+        let length2 = segment.sq_len();
+        if length2 == 0.0 { return None }
+        let w = (-cp[0].x * segment.x - cp[0].y * segment.y) / length2;
+
+        let hit_u = lerp(w, u[0], u[1]).clamp(u[0], u[1]);
+        let hit_width = self.width_at(hit_u);
+        let (center, tangent) = eval_bezier(cp, w.clamp(0.0, 1.0));
+
+        let distance2 = center.x * center.x + center.y * center.y;
+        if distance2 > hit_width * hit_width / 4.0 { return None }
+        if center.z < z_min || center.z > z_max { return None }
+
+        let distance = distance2.sqrt();
+        let across = tangent.x * -center.y + center.x * tangent.y;
+        let v = match across > 0.0 {
+            true => 0.5 + distance / hit_width,
+            false => 0.5 - distance / hit_width,
+        };
+
+        Some(CurveHit {
+            z: center.z,
+            u: hit_u,
+            v,
+            side: Vec3::new(-center.x, -center.y, 0.0),
+            tangent: tangent / (u[1] - u[0]),
+        })
+    }
+}
+
+
+fn lerp(t: f64, a: f64, b: f64) -> f64 {
+    (1.0 - t) * a + t * b
+}
+
+// point and derivative at t, by de casteljau
+fn eval_bezier(cp: &[Vec3; 4], t: f64) -> (Vec3, Vec3) {
+    let mix = |a: Vec3, b: Vec3| (1.0 - t) * a + t * b;
+    let (a, b, c) = (mix(cp[0], cp[1]), mix(cp[1], cp[2]), mix(cp[2], cp[3]));
+    let (d, e) = (mix(a, b), mix(b, c));
+    (mix(d, e), 3.0 * (e - d))
+}
+
+// halves of a curve, sharing the middle point
+fn split(cp: &[Vec3; 4]) -> [Vec3; 7] {
+    [
+        cp[0],
+        (cp[0] + cp[1]) / 2.0,
+        (cp[0] + 2.0 * cp[1] + cp[2]) / 4.0,
+        (cp[0] + 3.0 * cp[1] + 3.0 * cp[2] + cp[3]) / 8.0,
+        (cp[1] + 2.0 * cp[2] + cp[3]) / 4.0,
+        (cp[2] + cp[3]) / 2.0,
+        cp[3],
+    ]
+}
+
+// control points of the part of a curve between u0 and u1
+fn restrict(cp: &[Vec3; 4], u0: f64, u1: f64) -> [Vec3; 4] {
+    let blossom = |a: f64, b: f64, c: f64| {
+        let mix = |t: f64, p: Vec3, q: Vec3| (1.0 - t) * p + t * q;
+        let first = [mix(a, cp[0], cp[1]), mix(a, cp[1], cp[2]), mix(a, cp[2], cp[3])];
+        let second = [mix(b, first[0], first[1]), mix(b, first[1], first[2])];
+        mix(c, second[0], second[1])
+    };
+    [blossom(u0, u0, u0), blossom(u0, u0, u1), blossom(u0, u1, u1), blossom(u1, u1, u1)]
+}
+
+
+impl Intersect for Curve {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let speed = ray.direction().len();
+        let forward = ray.direction() / speed;
+        let frame = Frame::new(forward);
+        let cp = self.points.map(|p| frame.to_local(p - ray.origin()));
+
+        let hit = self.recurse(&cp, self.u, self.depth, t_min * speed, t_max * speed)?;
+
+        let dpdu = frame.to_world(hit.tangent);
+        let along = dpdu.unit();
+
+        // the band faces the ray, square to the curve
+        let facing = forward - along * forward.dot(along);
+        let flat = match facing.near_zero() {
+            true => -forward,
+            false => -facing.unit(),
+        };
+        let dpdv = along.cross(flat) * self.width_at(hit.u);
+
+        let normal = match self.kind {
+            CurveKind::Flat => flat,
+            // tilted towards the side of the hit like on a round fiber
+            CurveKind::Cylinder => {
+                let side = frame.to_world(hit.side);
+                let side = side - along * side.dot(along);
+                let s = (2.0 * side.len() / self.width_at(hit.u)).min(1.0);
+                match side.near_zero() {
+                    true => flat,
+                    false => (flat * (1.0 - s * s).sqrt() + side.unit() * s).unit(),
+                }
+            },
+        };
+
+        Some(
+            IntersectResult::new(ray, hit.z / speed, normal, self.material)
+                .with_uv(hit.u, hit.v)
+                .with_tangents(dpdu, dpdv)
+        )
+    }
+
+    fn bbox(&self) -> Aabb {
+        let half = self.width_at(self.u[0]).max(self.width_at(self.u[1])) / 2.0;
+        let margin = Vec3::new(half, half, half);
+
+        let mut lower = Vec3::inf();
+        let mut upper = Vec3::neg_inf();
+        for point in self.points {
+            for i in 0..3 {
+                if point[i] < lower[i] { lower[i] = point[i] }
+                if point[i] > upper[i] { upper[i] = point[i] }
+            }
+        }
+
+        Aabb { lower: lower - margin, upper: upper + margin }
+    }
+
+    // a whole curve splits into pieces with bounds of their own, each
+    // halved fewer times when hit
+    fn divide(&self) -> Option<Vec<Object>> {
+        let splits = self.depth.min(MAX_SPLITS);
+        if self.u != [0.0, 1.0] || splits == 0 { return None }
+
+        let count = 1 << splits;
+        let pieces = (0..count)
+            .map(|i| {
+                let u = [i as f64 / count as f64, (i + 1) as f64 / count as f64];
+                let points = restrict(&self.points, u[0], u[1]);
+                Self::piece(points, self.width, self.kind, self.material, u).into()
+            })
+            .collect();
+        Some(pieces)
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "<Curve [ points: {:?}, width: {:?}, u: {:?} ]>",
+            self.points, self.width, self.u
+        )
+    }
+}
+
+
+// a hair passing through its points, thinning from root to tip. read from
+// a minimal text format, one strand per line:
+//
+//     # root width, tip width, then x y z of every point
+//     0.02 0.005  0 0 0  0 1 0  0.3 2 0.1
+//
+// blank lines and lines starting with # are skipped
+#[derive(Debug, Clone)]
+pub struct Strand {
+    pub points: Vec<Vec3>,
+    pub width: [f64; 2],
+}
+
+
+impl Strand {
+
+    pub fn from_file(filename: &str) -> Vec<Strand> {
+        let path = Path::new(filename);
+        let text = fs::read_to_string(path)
+            .unwrap_or_else(|_| panic!("File not found! - {}", path.display()));
+
+        Strand::from_str(&text)
+            .unwrap_or_else(|e| panic!("Unable to load {} - {}", path.display(), e))
+    }
+
+    pub fn from_str(text: &str) -> Result<Vec<Strand>, String> {
+        let mut strands = vec![];
+
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue }
+
+            let values: Vec<f64> = line
+                .split_ascii_whitespace()
+                .map(|s| s.parse::<f64>().map_err(|_| {
+                    format!("invalid number '{}' on line {}", s, number + 1)
+                }))
+                .collect::<Result<Vec<f64>, String>>()?;
+
+            let coordinates = values.len().saturating_sub(2);
+            if coordinates < 6 || !coordinates.is_multiple_of(3) {
+                return Err(format!(
+                    "expected two widths and at least two points on line {}", number + 1
+                ));
+            }
+
+            strands.push(Strand {
+                points: values[2..]
+                    .chunks(3)
+                    .map(|p| Vec3::new(p[0], p[1], p[2]))
+                    .collect(),
+                width: [values[0], values[1]],
+            });
+        }
+
+        Ok(strands)
+    }
+
+    // one bezier per pair of points, bent like a catmull-rom spline so
+    // the strand runs smoothly through all of them
+    pub fn curves(&self, kind: CurveKind, material: usize) -> Vec<Object> {
+        let p = &self.points;
+        let last = p.len() - 1;
+        let width = |i: usize| lerp(i as f64 / last as f64, self.width[0], self.width[1]);
+
+        (0..last)
+            .map(|i| {
+                let before = p[i.saturating_sub(1)];
+                let after = p[(i + 2).min(last)];
+                let points = [
+                    p[i],
+                    p[i] + (p[i + 1] - before) / 6.0,
+                    p[i + 1] - (after - p[i]) / 6.0,
+                    p[i + 1],
+                ];
+                Curve::new(points, [width(i), width(i + 1)], kind, material)
+            })
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn curve_hits() {
+        // straight along x, 0.2 wide
+        let line = [
+            Vec3::zero(), Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0), Vec3::new(3.0, 0.0, 0.0),
+        ];
+        let flat = Curve::new(line, [0.2, 0.2], CurveKind::Flat, 0);
+        let round = Curve::new(line, [0.2, 0.2], CurveKind::Cylinder, 0);
+        let down = |x: f64, y: f64| Ray::new(Vec3::new(x, y, 5.0), Vec3::new(0.0, 0.0, -2.0));
+
+        let hit = flat.intersect(&down(1.5, 0.0), 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 2.5).abs() < 1e-9);
+        assert!((hit.u - 0.5).abs() < 1e-9 && (hit.v - 0.5).abs() < 1e-9);
+        assert!((hit.normal - Vec3::new(0.0, 0.0, 1.0)).len() < 1e-9);
+        assert!((hit.tangent.unit() - Vec3::new(1.0, 0.0, 0.0)).len() < 1e-9);
+
+        assert!(flat.intersect(&down(1.5, 0.09), 0.001, f64::INFINITY).is_some());
+        assert!(flat.intersect(&down(1.5, 0.11), 0.001, f64::INFINITY).is_none());
+        assert!(flat.intersect(&down(3.2, 0.0), 0.001, f64::INFINITY).is_none());
+        assert!(flat.intersect(&down(1.5, 0.0), 0.001, 2.0).is_none());
+
+        // halfway to the edge of a round fiber the normal leans 30 degrees
+        let hit = round.intersect(&down(1.5, 0.05), 0.001, f64::INFINITY).unwrap();
+        assert!((hit.normal.y - 0.5).abs() < 1e-9);
+
+        // a bent curve split for the bvh is hit where the whole one is
+        let bent = Curve::new(
+            [
+                Vec3::zero(), Vec3::new(0.0, 2.0, 0.0),
+                Vec3::new(2.0, 2.0, 0.0), Vec3::new(2.0, 0.0, 0.0),
+            ],
+            [0.05, 0.01],
+            CurveKind::Flat,
+            0,
+        );
+        let pieces = bent.divide().unwrap();
+        assert!(pieces.len() > 1 && pieces.iter().all(|piece| piece.divide().is_none()));
+        let Object::Curve(curve) = &bent else { panic!("not a curve") };
+        for u in [0.1, 0.3, 0.5, 0.8] {
+            let (p, _) = eval_bezier(&curve.points, u);
+            let whole = bent.intersect(&down(p.x, p.y), 0.001, f64::INFINITY).unwrap();
+            let split = pieces
+                .iter()
+                .filter_map(|piece| piece.intersect(&down(p.x, p.y), 0.001, f64::INFINITY))
+                .min_by(|a, b| a.t.total_cmp(&b.t))
+                .unwrap();
+            assert!((whole.u - u).abs() < 1e-2 && (split.u - u).abs() < 1e-2);
+            assert!((whole.t - split.t).abs() < 1e-9);
+        }
+        let hit = bent.intersect(&down(1.0, 1.5), 0.001, f64::INFINITY).unwrap();
+        assert!((hit.u - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn strand_file() {
+        let text = "# two strands\n\n0.02 0.01  0 0 0  0 1 0  0 2 1\n0.01 0.01 0 0 0 1 1 1\n";
+        let strands = Strand::from_str(text).unwrap();
+        assert_eq!(strands.len(), 2);
+        assert_eq!(strands[0].points.len(), 3);
+        assert_eq!(strands[0].width, [0.02, 0.01]);
+
+        // the curves meet at the given points
+        let curves = strands[0].curves(CurveKind::Flat, 0);
+        assert_eq!(curves.len(), 2);
+        match (&curves[0], &curves[1]) {
+            (Object::Curve(a), Object::Curve(b)) => {
+                assert_eq!(a.points[3], b.points[0]);
+                assert_eq!(b.points[0], Vec3::new(0.0, 1.0, 0.0));
+                assert_eq!(a.width[1], 0.015);
+            },
+            _ => panic!("not curves"),
+        }
+
+        assert!(Strand::from_str("0.01 0.01 0 0 0").is_err());
+        assert!(Strand::from_str("0.01 0.01 0 0 0 1 1").is_err());
+        assert!(Strand::from_str("0.01 x 0 0 0 1 1 1").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+use std::fmt;
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+use crate::objects::disk::{ disk_uv, disk_bbox };
+use crate::materials::microfacet::Frame;
+
+use rand::Rng;
+
+
+// closed cylinder standing on the disk around `base`, `height` along `axis`.
+// on the side u goes around and v up, the caps are mapped like disks
+#[derive(Debug, Clone)]
+pub struct Cylinder {
+    pub base: Vec3,
+    pub axis: Vec3,
+    pub radius: f64,
+    pub height: f64,
+    frame: Frame,
+    pub material: usize,
+}
+
+
+impl Cylinder {
+
+    pub fn new(
+        base: Vec3, axis: Vec3, radius: f64, height: f64, material: usize
+    ) -> Object {
+        let axis = axis.unit();
+        Self { base, axis, radius, height, frame: Frame::new(axis), material }.into()
+    }
+
+    pub fn area(&self) -> f64 {
+        2.0 * PI * self.radius * (self.radius + self.height)
+    }
+
+    // point and normal picked uniformly by area, side or one of the caps
+    pub fn sample(&self) -> (Vec3, Vec3) {
+        let mut rng = rand::thread_rng();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let (sin, cos) = phi.sin_cos();
+
+        let side = self.height / (self.radius + self.height);
+        let pick = rng.gen::<f64>();
+        let (local, normal) = if pick < side {
+            let z = self.height * rng.gen::<f64>();
+            (Vec3::new(self.radius * cos, self.radius * sin, z), Vec3::new(cos, sin, 0.0))
+        } else {
+            let rho = self.radius * rng.gen::<f64>().sqrt();
+            let top = pick > side + (1.0 - side) / 2.0;
+            let (z, nz) = if top { (self.height, 1.0) } else { (0.0, -1.0) };
+            (Vec3::new(rho * cos, rho * sin, z), Vec3::new(0.0, 0.0, nz))
+        };
+
+        (self.base + self.frame.to_world(local), self.frame.to_world(normal))
+    }
+}
+
+
+impl Intersect for Cylinder {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let o = self.frame.to_local(ray.origin() - self.base);
+        let d = self.frame.to_local(ray.direction());
+        let (r, h) = (self.radius, self.height);
+
+        // nearest of the side and both caps, with its local outward normal
+        let mut nearest: Option<(f64, Vec3)> = None;
+        let mut consider = |t: f64, normal: Vec3| {
+            if t >= t_min && t <= t_max && nearest.is_none_or(|(best, _)| t < best) {
+                nearest = Some((t, normal));
+            }
+        };
+
+        // side, rays parallel to the axis never hit it
+        let a = d.x * d.x + d.y * d.y;
+        if a > 1e-12 {
+            let half_b = o.x * d.x + o.y * d.y;
+            let c = o.x * o.x + o.y * o.y - r * r;
+            let discriminant = half_b * half_b - a * c;
+            if discriminant >= 0.0 {
+                let sqrtd = discriminant.sqrt();
+                for t in [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a] {
+                    let p = o + t * d;
+                    if p.z >= 0.0 && p.z <= h {
+                        consider(t, Vec3::new(p.x / r, p.y / r, 0.0));
+                    }
+                }
+            }
+        }
+
+        // caps, rays across the axis never hit them
+        if d.z.abs() > 1e-12 {
+            for (z, nz) in [(0.0, -1.0), (h, 1.0)] {
+                let t = (z - o.z) / d.z;
+                let p = o + t * d;
+                if p.x * p.x + p.y * p.y <= r * r {
+                    consider(t, Vec3::new(0.0, 0.0, nz));
+                }
+            }
+        }
+
+        let (t, normal) = nearest?;
+        let p = o + t * d;
+        let (u, v, dpdu, dpdv) = match normal.z == 0.0 {
+            true => {
+                let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+                let dpdu = 2.0 * PI * Vec3::new(-p.y, p.x, 0.0);
+                (phi / (2.0 * PI), p.z / h, dpdu, Vec3::new(0.0, 0.0, h))
+            },
+            false => disk_uv(p, r),
+        };
+
+        Some(
+            IntersectResult::new(ray, t, self.frame.to_world(normal), self.material)
+                .with_uv(u, v)
+                .with_tangents(self.frame.to_world(dpdu), self.frame.to_world(dpdv))
+                .sampled()
+        )
+    }
+
+    // both cap disks bound the whole cylinder
+    fn bbox(&self) -> Aabb {
+        let top = self.base + self.height * self.axis;
+        disk_bbox(self.base, self.axis, self.radius)
+            .merge(disk_bbox(top, self.axis, self.radius))
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "<Cylinder [ base: {:?}, axis: {:?}, radius: {}, height: {} ]>",
+            self.base, self.axis, self.radius, self.height
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cylinder_hits() {
+        let cylinder = Cylinder::new(Vec3::zero(), Vec3::new(0.0, 1.0, 0.0), 1.0, 2.0, 0);
+        let hit = |origin: Vec3, direction: Vec3| {
+            cylinder.intersect(&Ray::new(origin, direction), 0.001, f64::INFINITY)
+        };
+
+        // side from outside, normal points out
+        let result = hit(Vec3::new(5.0, 1.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)).unwrap();
+        assert!((result.t - 4.0).abs() < 1e-12);
+        assert!((result.normal - Vec3::new(1.0, 0.0, 0.0)).len() < 1e-12);
+        assert!((result.v - 0.5).abs() < 1e-12);
+
+        // top cap from above, and the bottom cap from inside
+        let result = hit(Vec3::new(0.3, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((result.t - 3.0).abs() < 1e-12);
+        assert!((result.normal - Vec3::new(0.0, 1.0, 0.0)).len() < 1e-12);
+        let result = hit(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((result.t - 1.0).abs() < 1e-12);
+        assert!(!result.front_face);
+
+        // from inside out through the side
+        let result = hit(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0)).unwrap();
+        assert!((result.t - 1.0).abs() < 1e-12);
+        assert!(!result.front_face);
+
+        // grazing the side along the axis, above the top and past the side
+        assert!(hit(Vec3::new(1.0001, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+        assert!(hit(Vec3::new(5.0, 2.5, 0.0), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+        assert!(hit(Vec3::new(5.0, 1.0, 1.0001), Vec3::new(-1.0, 0.0, 0.0)).is_none());
+
+        let bbox = cylinder.bbox();
+        assert!((bbox.upper - Vec3::new(1.0, 2.0, 1.0)).len() < 0.001);
+        assert!((bbox.lower - Vec3::new(-1.0, 0.0, -1.0)).len() < 0.001);
+    }
+}
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+use crate::materials::microfacet::Frame;
+
+use rand::Rng;
+
+
+// flat disk facing along `normal`, u goes around it and v from the center
+// to the rim
+#[derive(Debug, Clone)]
+pub struct Disk {
+    pub center: Vec3,
+    pub normal: Vec3,
+    pub radius: f64,
+    frame: Frame,
+    pub material: usize,
+}
+
+
+impl Disk {
+
+    pub fn new(center: Vec3, normal: Vec3, radius: f64, material: usize) -> Object {
+        let normal = normal.unit();
+        Self { center, normal, radius, frame: Frame::new(normal), material }.into()
+    }
+
+    pub fn area(&self) -> f64 {
+        PI * self.radius * self.radius
+    }
+
+    // point and normal picked uniformly by area
+    pub fn sample(&self) -> (Vec3, Vec3) {
+        let mut rng = rand::thread_rng();
+        let rho = self.radius * rng.gen::<f64>().sqrt();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let local = Vec3::new(rho * phi.cos(), rho * phi.sin(), 0.0);
+        (self.center + self.frame.to_world(local), self.normal)
+    }
+}
+
+
+// uvs and their derivatives of a point in the xy plane of a local frame,
+// shared with the caps of cylinders and cones
+pub fn disk_uv(p: Vec3, radius: f64) -> (f64, f64, Vec3, Vec3) {
+    let rho = (p.x * p.x + p.y * p.y).sqrt();
+    let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+
+    let dpdu = 2.0 * PI * Vec3::new(-p.y, p.x, 0.0);
+    let dpdv = match rho > 1e-8 {
+        true => Vec3::new(p.x, p.y, 0.0) * (radius / rho),
+        false => Vec3::zero(),
+    };
+    (phi / (2.0 * PI), rho / radius, dpdu, dpdv)
+}
+
+
+// bounds of a disk, its extent along an axis shrinks the more the axis
+// lines up with the normal
+pub fn disk_bbox(center: Vec3, normal: Vec3, radius: f64) -> Aabb {
+    let extent = |n: f64| radius * (1.0 - n * n).max(0.0).sqrt() + 0.00001;
+    let extent = Vec3::new(extent(normal.x), extent(normal.y), extent(normal.z));
+    Aabb { lower: center - extent, upper: center + extent }
+}
+
+
+impl Intersect for Disk {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let denom = self.normal.dot(ray.direction());
+        if denom.abs() < 1e-12 { return None }
+
+        let t = (self.center - ray.origin()).dot(self.normal) / denom;
+        if t < t_min || t > t_max { return None }
+
+        let local = self.frame.to_local(ray.at(t) - self.center);
+        if local.x * local.x + local.y * local.y > self.radius * self.radius {
+            return None
+        }
+
+        let (u, v, dpdu, dpdv) = disk_uv(local, self.radius);
+        Some(
+            IntersectResult::new(ray, t, self.normal, self.material)
+                .with_uv(u, v)
+                .with_tangents(self.frame.to_world(dpdu), self.frame.to_world(dpdv))
+                .sampled()
+        )
+    }
+
+    fn bbox(&self) -> Aabb {
+        disk_bbox(self.center, self.normal, self.radius)
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "<Disk [ center: {:?}, normal: {:?}, radius: {} ]>",
+            self.center, self.normal, self.radius
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disk_hits() {
+        let disk = Disk::new(Vec3::zero(), Vec3::new(0.0, 0.0, 1.0), 2.0, 0);
+
+        let hit = |origin: Vec3, direction: Vec3| {
+            disk.intersect(&Ray::new(origin, direction), 0.001, f64::INFINITY)
+        };
+        let down = Vec3::new(0.0, 0.0, -1.0);
+
+        let result = hit(Vec3::new(1.0, 0.0, 5.0), down).unwrap();
+        assert_eq!(result.t, 5.0);
+        assert!((0.0..1.0).contains(&result.u) && (result.v - 0.5).abs() < 1e-12);
+        // on the rim counts, just outside doesn't
+        assert!(hit(Vec3::new(0.0, 2.0, 5.0), down).is_some());
+        assert!(hit(Vec3::new(0.0, 2.001, 5.0), down).is_none());
+        // grazing in its plane
+        assert!(hit(Vec3::new(-5.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+        // from behind
+        let result = hit(Vec3::new(0.5, 0.5, -1.0), -down).unwrap();
+        assert!(!result.front_face);
+
+        let tilted = Disk::new(Vec3::zero(), Vec3::new(1.0, 0.0, 1.0), 1.0, 0).bbox();
+        assert!((tilted.upper.y - 1.0).abs() < 0.001);
+        assert!((tilted.upper.x - 0.5_f64.sqrt()).abs() < 0.001);
+    }
+}
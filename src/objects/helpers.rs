@@ -16,6 +16,9 @@ pub fn rect_ver(v0: Vec3, v1: Vec3, mat: usize) -> Mesh {
         vec![(0, 0), (1, 0), (3, 0), (1, 0), (2, 0), (3, 0)],
         vec![],
         mat,
+    ).with_texcoords(
+        vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+        vec![0, 1, 3, 1, 2, 3],
     )
 }
 
@@ -31,6 +34,9 @@ pub fn rect_hor(v0: Vec3, v1: Vec3, mat: usize) -> Mesh {
         vec![(0, 0), (1, 0), (2, 0), (0, 0), (2, 0), (3, 0)],
         vec![],
         mat,
+    ).with_texcoords(
+        vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+        vec![0, 1, 2, 0, 2, 3],
     )
 }
 
@@ -46,6 +52,9 @@ pub fn rect_hor_rev(v0: Vec3, v1: Vec3, mat: usize) -> Mesh {
         vec![(0, 0), (2, 0), (1, 0), (0, 0), (3, 0), (2, 0)],
         vec![],
         mat,
+    ).with_texcoords(
+        vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)],
+        vec![0, 2, 1, 0, 3, 2],
     )
 }
 
@@ -76,6 +85,25 @@ pub fn cuboid(p: Vec3, l: f64, h: f64, b: f64, mat: usize) -> Mesh {
 }
 
 
+// polygons fanned into triangles, keeping the polygons for subdivision
+#[allow(dead_code)]
+pub fn from_polygons(vertices: Vec<Vec3>, faces: Vec<Vec<usize>>, mat: usize) -> Mesh {
+    let indexes = faces
+        .iter()
+        .flat_map(|face| {
+            (1..face.len() - 1).flat_map(move |i| [(face[0], 0), (face[i], 0), (face[i + 1], 0)])
+        })
+        .collect();
+
+    let mut mesh = Mesh::new(vertices, indexes, vec![], mat);
+    mesh.polygons = faces
+        .into_iter()
+        .map(|face| face.into_iter().map(|v| (v, 0)).collect())
+        .collect();
+    mesh
+}
+
+
 pub fn from_obj(filename: String, mat: usize) -> Mesh {
 
     let txt = fs::read_to_string(Path::new(&filename))
@@ -83,10 +111,13 @@ pub fn from_obj(filename: String, mat: usize) -> Mesh {
 
     let mut vertices: Vec<Vec3> = vec![];
     let mut normals: Vec<Vec3> = vec![];
+    let mut texcoords: Vec<(f64, f64)> = vec![];
     let mut indexes: Vec<(usize, usize)> = vec![];
+    let mut texcoord_indexes: Vec<usize> = vec![];
     let mut face_indexes: Vec<usize> = vec![];
 
     let mut not_trig_mesh = false;
+    let mut missing_texcoords = false;
 
     for line in txt.lines() {
         let opts: Vec<&str> = line.split(" ").filter(|x| *x != "").collect();
@@ -107,6 +138,12 @@ pub fn from_obj(filename: String, mat: usize) -> Mesh {
                     opts[3].parse().unwrap(),
                 ))
             },
+            "vt" => {
+                texcoords.push((
+                    opts[1].parse().unwrap(),
+                    opts.get(2).map_or(0.0, |v| v.parse().unwrap()),
+                ))
+            },
             "f" => {
                 if opts.len() > 4 { not_trig_mesh = true };
 
@@ -122,27 +159,51 @@ pub fn from_obj(filename: String, mat: usize) -> Mesh {
                         values[2].parse().unwrap()
                     } else { 0 };
 
+                    let t_index: usize = if values.len() >= 2 && !values[1].is_empty() {
+                        values[1].parse().unwrap()
+                    } else {
+                        missing_texcoords = true;
+                        1
+                    };
+
                     indexes.push((index - 1, n_index - 1));
+                    texcoord_indexes.push(t_index - 1);
                 }
             },
             _ => {}
         };
     }
 
+    let mut polygons = vec![];
     if not_trig_mesh {
+        let mut k = 0;
+        for amt in face_indexes.iter() {
+            polygons.push((k..k + amt).map(|n| (indexes[n].0, texcoord_indexes[n])).collect());
+            k += amt;
+        }
+
         let mut new_indexes = vec![];
+        let mut new_texcoord_indexes = vec![];
         let mut k = 0;
         for i in 0..face_indexes.len() {
             for j in 0..(face_indexes[i] - 2) {
-                new_indexes.push(indexes[k]);
-                new_indexes.push(indexes[k + j + 1]);
-                new_indexes.push(indexes[k + j + 2]);
+                for n in [k, k + j + 1, k + j + 2] {
+                    new_indexes.push(indexes[n]);
+                    new_texcoord_indexes.push(texcoord_indexes[n]);
+                }
             }
             k += face_indexes[i];
         }
 
         indexes = new_indexes;
+        texcoord_indexes = new_texcoord_indexes;
     }
 
-    Mesh::new(vertices, indexes, normals, mat)
+    let mut mesh = Mesh::new(vertices, indexes, normals, mat);
+    mesh.polygons = polygons;
+
+    match missing_texcoords || texcoords.is_empty() {
+        true => mesh,
+        false => mesh.with_texcoords(texcoords, texcoord_indexes),
+    }
 }
@@ -0,0 +1,166 @@
+use std::fmt;
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use crate::{ Ray, Matrix4 };
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::materials::Material;
+use crate::objects::{ Aabb, Object, Mesh, Triangle, BvhNode, Transform };
+
+
+// prototypes already sealed, by their address and the material override
+// they were sealed with, so instances sharing a prototype share its copy
+pub type Sealed = HashMap<(usize, Option<usize>), Arc<Prototype>>;
+
+
+// geometry built into its own bvh once, then placed any number of times
+// by instances that share it
+#[derive(Debug)]
+pub struct Prototype {
+    pub root: usize,
+    pub objects: Vec<Object>,
+    pub nodes: Vec<BvhNode>,
+}
+
+
+impl Prototype {
+
+    pub fn new(world: Vec<Object>) -> Arc<Prototype> {
+        let primitives: Vec<Object> = world
+            .into_iter()
+            .flat_map(|object| match object.divide() {
+                Some(inner_objs) => inner_objs,
+                None => vec![object],
+            })
+            .collect();
+
+        let mut objects: Vec<Object> = vec![];
+        let mut nodes: Vec<BvhNode> = vec![];
+        let root = BvhNode::construct(primitives, &mut objects, &mut nodes);
+
+        Arc::new(Self { root, objects, nodes })
+    }
+
+    // copy of the prototype with the meshes that end up with a transmissive
+    // material double sided, see Object::seal. the bvh is kept as it is,
+    // the prototype itself comes back when nothing needs sealing
+    pub fn sealed(
+        prototype: &Arc<Prototype>, materials: &[Material], material: Option<usize>,
+        sealed: &mut Sealed,
+    ) -> Arc<Prototype> {
+        let key = (Arc::as_ptr(prototype) as usize, material);
+        if let Some(copy) = sealed.get(&key) {
+            return Arc::clone(copy);
+        }
+
+        // the triangles of a mesh share one sealed copy of it
+        let mut meshes: HashMap<usize, Arc<Mesh>> = HashMap::new();
+        let mut changed = false;
+        let mut objects = prototype.objects.clone();
+        for object in objects.iter_mut() {
+            changed |= match object {
+                Object::Triangle(Triangle { mesh, .. }) => {
+                    let seal = !mesh.double_sided
+                        && materials[material.unwrap_or(mesh.material)].transmissive(materials, 0);
+                    if seal {
+                        let copy = meshes
+                            .entry(Arc::as_ptr(mesh) as usize)
+                            .or_insert_with(|| {
+                                Arc::new(Mesh { double_sided: true, ..(**mesh).clone() })
+                            });
+                        *mesh = Arc::clone(copy);
+                    }
+                    seal
+                },
+                object => object.seal(materials, material, sealed),
+            };
+        }
+
+        let copy = match changed {
+            true => Arc::new(Self { objects, nodes: prototype.nodes.clone(), ..**prototype }),
+            false => Arc::clone(prototype),
+        };
+        sealed.insert(key, Arc::clone(&copy));
+        copy
+    }
+}
+
+
+// a prototype moved into the world by a transform. rays are taken into
+// the space of the prototype instead of copying its geometry, so a
+// thousand instances cost no more memory than one
+#[derive(Debug, Clone)]
+pub struct Instance {
+    pub prototype: Arc<Prototype>,
+    pub transform: Transform,
+    // replaces the materials of the prototype
+    pub material: Option<usize>,
+}
+
+
+impl Instance {
+
+    pub fn new(prototype: &Arc<Prototype>, transform: Matrix4) -> Object {
+        Self::placed(prototype, transform, None).into()
+    }
+
+    pub fn with_material(
+        prototype: &Arc<Prototype>, transform: Matrix4, material: usize
+    ) -> Object {
+        Self::placed(prototype, transform, Some(material)).into()
+    }
+
+    pub fn placed(
+        prototype: &Arc<Prototype>, transform: Matrix4, material: Option<usize>
+    ) -> Self {
+        Self {
+            prototype: Arc::clone(prototype),
+            transform: Transform::new(transform),
+            material,
+        }
+    }
+}
+
+
+impl Intersect for Instance {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let Prototype { root, objects, nodes } = &*self.prototype;
+        let node = &nodes[*root];
+
+        // the meshes and loose objects of the world sit in place, their
+        // rays and hits are used as they are
+        let mut result = match self.transform.identity {
+            true => node.intersect(ray, t_min, t_max, objects, nodes)?,
+            false => {
+                let local = self.transform.ray(ray);
+                let result = node.intersect(&local, t_min, t_max, objects, nodes)?;
+                self.transform.result(ray, result)
+            },
+        };
+
+        if let Some(material) = self.material {
+            result.material = material;
+        }
+
+        Some(result)
+    }
+
+    fn bbox(&self) -> Aabb {
+        self.transform.bbox(&self.prototype.nodes[self.prototype.root].bbox)
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "<Instance [ objects: {}, transform: {:?}, material: {:?} ]>",
+            self.prototype.objects.len(), self.transform.matrix, self.material
+        )
+    }
+}
+
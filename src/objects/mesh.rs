@@ -1,10 +1,12 @@
 use core::f64;
 use std::fmt;
 use std::sync::Arc;
+use std::collections::HashMap;
 
 use crate::{ Vec3, Ray, Matrix4 };
 use crate::behaviors::{Intersect, IntersectResult};
 use crate::objects::{ Aabb, Triangle, Object };
+use crate::objects::subdivision::{ self, Subdivision, Scheme, DEFAULT_CREASE_ANGLE };
 
 
 #[derive(Debug, Clone)]
@@ -12,9 +14,21 @@ pub struct Mesh {
     pub vertices: Vec<Vec3>,
     pub normals: Vec<Vec3>,
     pub indexes: Vec<(usize, usize)>,
+    pub texcoords: Vec<(f64, f64)>,
+    pub texcoord_indexes: Vec<usize>,
+    // per index tangent + bitangent sign, generated from texcoords
+    pub tangents: Vec<(Vec3, f64)>,
     pub index_amt: usize,
     // normal: Vec3,
     pub material: usize,
+    // hit from behind too, closed boundaries of media need to be found
+    // by rays leaving them
+    pub double_sided: bool,
+    // faces as loaded, (vertex, texcoord) per corner, kept when they
+    // aren't all triangles so subdivision can work on the quads
+    pub polygons: Vec<Vec<(usize, usize)>>,
+    // applied when the mesh is divided into triangles
+    pub subdivision: Option<Subdivision>,
 }
 
 impl Mesh {
@@ -31,11 +45,125 @@ impl Mesh {
 
         Self {
             index_amt, vertices, indexes, normals,
+            texcoords: vec![],
+            texcoord_indexes: vec![],
+            tangents: vec![],
             material: mat,
+            double_sided: false,
+            polygons: vec![],
+            subdivision: None,
             // normal: Vec3::zero(),
         }
     }
 
+    pub fn with_texcoords(
+        mut self, texcoords: Vec<(f64, f64)>, texcoord_indexes: Vec<usize>
+    ) -> Self {
+        if texcoord_indexes.len() != self.index_amt {
+            panic!("texcoord indices do not match vertex indices in mesh");
+        }
+        self.texcoords = texcoords;
+        self.texcoord_indexes = texcoord_indexes;
+        self
+    }
+
+    // refined before it is divided, quads by catmull-clark and
+    // triangles by loop. every level quadruples the faces
+    #[allow(dead_code)]
+    pub fn with_subdivision(mut self, scheme: Scheme, levels: usize) -> Self {
+        self.subdivision = Some(Subdivision {
+            scheme, levels, crease_angle: DEFAULT_CREASE_ANGLE
+        });
+        self
+    }
+
+    // edges bent further than this many degrees stay hard
+    #[allow(dead_code)]
+    pub fn with_crease_angle(mut self, degrees: f64) -> Self {
+        match self.subdivision.as_mut() {
+            Some(subdivision) => subdivision.crease_angle = degrees,
+            None => panic!("crease angle set on mesh without subdivision"),
+        }
+        self
+    }
+
+    // derivatives of position with respect to texcoords for one triangle
+    pub fn face_tangents(&self, offset: usize) -> Option<(Vec3, Vec3)> {
+        if self.texcoords.is_empty() { return None }
+
+        let v0 = self.vertices[self.indexes[offset].0];
+        let v1 = self.vertices[self.indexes[offset + 1].0];
+        let v2 = self.vertices[self.indexes[offset + 2].0];
+        let t0 = self.texcoords[self.texcoord_indexes[offset]];
+        let t1 = self.texcoords[self.texcoord_indexes[offset + 1]];
+        let t2 = self.texcoords[self.texcoord_indexes[offset + 2]];
+
+        let (e1, e2) = (v1 - v0, v2 - v0);
+        let (du1, dv1) = (t1.0 - t0.0, t1.1 - t0.1);
+        let (du2, dv2) = (t2.0 - t0.0, t2.1 - t0.1);
+
+        let det = du1 * dv2 - du2 * dv1;
+        if det.abs() < 1e-12 { return None }
+
+        let r = 1.0 / det;
+        Some((
+            (e1 * dv2 - e2 * dv1) * r,
+            (e2 * du1 - e1 * du2) * r,
+        ))
+    }
+
+    // mikktspace style per vertex tangents: face tangents are accumulated on
+    // every vertex sharing the same position, normal and texcoord, then
+    // orthogonalized against the vertex normal with the bitangent handedness
+    // kept as a sign, so bitangent = sign * normal x tangent when shading
+    pub fn generate_tangents(&mut self) {
+        if self.texcoords.is_empty() { return }
+
+        let key = |i: usize| (
+            self.indexes[i].0, self.indexes[i].1, self.texcoord_indexes[i]
+        );
+
+        let mut sums: HashMap<(usize, usize, usize), (Vec3, Vec3)> = HashMap::new();
+        for i in (0..self.index_amt).step_by(3) {
+            if let Some((dpdu, dpdv)) = self.face_tangents(i) {
+                for k in i..(i + 3) {
+                    let sum = sums.entry(key(k)).or_insert((Vec3::zero(), Vec3::zero()));
+                    sum.0 += dpdu;
+                    sum.1 += dpdv;
+                }
+            }
+        }
+
+        let mut tangents = Vec::with_capacity(self.index_amt);
+        for i in 0..self.index_amt {
+            let face = i - (i % 3);
+            let normal = match self.normals.is_empty() {
+                true => {
+                    let v0 = self.vertices[self.indexes[face].0];
+                    let v1 = self.vertices[self.indexes[face + 1].0];
+                    let v2 = self.vertices[self.indexes[face + 2].0];
+                    (v1 - v0).cross(v2 - v0).unit()
+                },
+                false => self.normals[self.indexes[i].1].unit(),
+            };
+
+            let (t, b) = sums.get(&key(i)).copied()
+                .unwrap_or((Vec3::zero(), Vec3::zero()));
+
+            // gram-schmidt, tangent stays zero if it is degenerate
+            let t = t - normal * normal.dot(t);
+            let t = match t.near_zero() {
+                true => Vec3::zero(),
+                false => t.unit(),
+            };
+            let sign = if normal.cross(t).dot(b) < 0.0 { -1.0 } else { 1.0 };
+
+            tangents.push((t, sign));
+        }
+
+        self.tangents = tangents;
+    }
+
     #[allow(dead_code)]
     pub fn transform(&mut self, matrix: Matrix4) {
         let normal_matrix = matrix.inverse().transpose();
@@ -47,6 +175,12 @@ impl Mesh {
         for normal in self.normals.iter_mut() {
             *normal = normal_matrix * (*normal);
         }
+
+        for (tangent, _) in self.tangents.iter_mut() {
+            if !tangent.near_zero() {
+                *tangent = matrix.transform_vector(*tangent).unit();
+            }
+        }
     }
 
     #[allow(dead_code)]
@@ -112,12 +246,20 @@ impl Intersect for Mesh {
     }
 
     fn divide(&self) -> Option<Vec<Object>> {
+        if let Some(refinement) = self.subdivision {
+            return subdivision::subdivide(self, refinement).divide();
+        }
+
         let mut triangles: Vec<Object> = Vec::with_capacity(
             self.index_amt / 3
         );
 
         let amt = self.index_amt;
-        let parent_mesh = Arc::new(self.clone());
+        let mut parent_mesh = self.clone();
+        if parent_mesh.tangents.is_empty() {
+            parent_mesh.generate_tangents();
+        }
+        let parent_mesh = Arc::new(parent_mesh);
         for i in (0..amt).step_by(3) {
             triangles.push(
                 Triangle {
@@ -0,0 +1,114 @@
+use std::fmt;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+
+
+// bounds of the plane along the axes it extends in, large instead of
+// infinite so the surface area heuristic of the bvh stays finite
+const EXTENT: f64 = 1e5;
+
+
+// infinite plane through `point`, uvs repeat every unit along the plane
+#[derive(Debug, Clone)]
+pub struct Plane {
+    pub point: Vec3,
+    pub normal: Vec3,
+    tangent: Vec3,
+    bitangent: Vec3,
+    pub material: usize,
+}
+
+
+impl Plane {
+
+    pub fn new(point: Vec3, normal: Vec3, material: usize) -> Object {
+        let normal = normal.unit();
+        let (tangent, bitangent) = normal.orthonormal_basis();
+        Self { point, normal, tangent, bitangent, material }.into()
+    }
+}
+
+
+impl Intersect for Plane {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        // rays along the plane never hit it
+        let denom = self.normal.dot(ray.direction());
+        if denom.abs() < 1e-12 { return None }
+
+        let t = (self.point - ray.origin()).dot(self.normal) / denom;
+        if t < t_min || t > t_max { return None }
+
+        let offset = ray.at(t) - self.point;
+        let u = offset.dot(self.tangent).rem_euclid(1.0);
+        let v = offset.dot(self.bitangent).rem_euclid(1.0);
+
+        Some(
+            IntersectResult::new(ray, t, self.normal, self.material)
+                .with_uv(u, v)
+                .with_tangents(self.tangent, self.bitangent)
+                .sampled()
+        )
+    }
+
+    // flat along an axis only if the plane faces straight along it
+    fn bbox(&self) -> Aabb {
+        let mut lower = Vec3::zero();
+        let mut upper = Vec3::zero();
+        for i in 0..3 {
+            let flat = self.normal[i].abs() == 1.0;
+            let extent = if flat { 0.00001 } else { EXTENT };
+            let center = if flat { self.point[i] } else { 0.0 };
+            lower[i] = center - extent;
+            upper[i] = center + extent;
+        }
+        Aabb { lower, upper }
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Plane [ point: {:?}, normal: {:?} ]>", self.point, self.normal)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plane_hits() {
+        let plane = Plane::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 1.0, 0.0), 0);
+
+        // from above and from below, the normal always faces the ray
+        let down = Ray::new(Vec3::new(3.0, 5.0, -7.0), Vec3::new(0.0, -1.0, 0.0));
+        let result = plane.intersect(&down, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(result.t, 4.0);
+        assert!(result.front_face);
+
+        let up = Ray::new(Vec3::new(0.0, -1.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        let result = plane.intersect(&up, 0.001, f64::INFINITY).unwrap();
+        assert_eq!(result.t, 2.0);
+        assert!(!result.front_face);
+        assert_eq!(result.normal, Vec3::new(0.0, -1.0, 0.0));
+        assert!((0.0..1.0).contains(&result.u) && (0.0..1.0).contains(&result.v));
+
+        // grazing along the plane, pointing away and out of range
+        let grazing = Ray::new(Vec3::new(0.0, 1.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        assert!(plane.intersect(&grazing, 0.001, f64::INFINITY).is_none());
+        let away = Ray::new(Vec3::new(0.0, 2.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        assert!(plane.intersect(&away, 0.001, f64::INFINITY).is_none());
+        assert!(plane.intersect(&down, 0.001, 3.0).is_none());
+
+        let bbox = plane.bbox();
+        assert!(bbox.upper.y - bbox.lower.y < 0.001 && bbox.upper.x > 1000.0);
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+
+use rand::Rng;
+
+
+// parallelogram spanned by the edges `u` and `v` from `corner`, its
+// uvs run along the edges and its normal is u x v
+#[derive(Debug, Clone)]
+pub struct Quad {
+    pub corner: Vec3,
+    pub u: Vec3,
+    pub v: Vec3,
+    normal: Vec3,
+    // turns a point in the plane into coordinates along the edges
+    w: Vec3,
+    pub material: usize,
+}
+
+
+impl Quad {
+
+    pub fn new(corner: Vec3, u: Vec3, v: Vec3, material: usize) -> Object {
+        let n = u.cross(v);
+        Self { corner, u, v, normal: n.unit(), w: n / n.dot(n), material }.into()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.u.cross(self.v).len()
+    }
+
+    // point and normal picked uniformly by area
+    pub fn sample(&self) -> (Vec3, Vec3) {
+        let mut rng = rand::thread_rng();
+        (self.corner + rng.gen::<f64>() * self.u + rng.gen::<f64>() * self.v, self.normal)
+    }
+}
+
+
+impl Intersect for Quad {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let denom = self.normal.dot(ray.direction());
+        if denom.abs() < 1e-12 { return None }
+
+        let t = (self.corner - ray.origin()).dot(self.normal) / denom;
+        if t < t_min || t > t_max { return None }
+
+        let planar = ray.at(t) - self.corner;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None
+        }
+
+        Some(
+            IntersectResult::new(ray, t, self.normal, self.material)
+                .with_uv(alpha, beta)
+                .with_tangents(self.u, self.v)
+                .sampled()
+        )
+    }
+
+    fn bbox(&self) -> Aabb {
+        let corners = [
+            self.corner, self.corner + self.u,
+            self.corner + self.v, self.corner + self.u + self.v,
+        ];
+        let margin = Vec3::new(0.00001, 0.00001, 0.00001);
+
+        let mut lower = Vec3::inf();
+        let mut upper = Vec3::neg_inf();
+        for corner in corners {
+            for i in 0..3 {
+                if corner[i] < lower[i] { lower[i] = corner[i] }
+                if corner[i] > upper[i] { upper[i] = corner[i] }
+            }
+        }
+
+        Aabb { lower: lower - margin, upper: upper + margin }
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Quad [ corner: {:?}, u: {:?}, v: {:?} ]>", self.corner, self.u, self.v)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quad_hits() {
+        // slanted parallelogram in the xy plane
+        let quad = Quad::new(
+            Vec3::zero(), Vec3::new(2.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 0.0), 0
+        );
+        let hit = |origin: Vec3, direction: Vec3| {
+            quad.intersect(&Ray::new(origin, direction), 0.001, f64::INFINITY)
+        };
+        let down = Vec3::new(0.0, 0.0, -1.0);
+
+        let result = hit(Vec3::new(2.0, 0.5, 3.0), down).unwrap();
+        assert_eq!(result.t, 3.0);
+        assert!((result.u - 0.75).abs() < 1e-12 && (result.v - 0.5).abs() < 1e-12);
+        assert!(result.front_face);
+
+        // left of the slanted edge, on a corner and from behind
+        assert!(hit(Vec3::new(0.2, 0.5, 3.0), down).is_none());
+        assert!(hit(Vec3::new(3.0, 1.0, 3.0), down).is_some());
+        assert!(!hit(Vec3::new(1.0, 0.5, -3.0), -down).unwrap().front_face);
+        // grazing in its plane
+        assert!(hit(Vec3::new(-1.0, 0.5, 0.0), Vec3::new(1.0, 0.0, 0.0)).is_none());
+
+        let bbox = quad.bbox();
+        assert!((bbox.upper.x - 3.0).abs() < 0.001 && (bbox.upper.y - 1.0).abs() < 0.001);
+    }
+}
@@ -0,0 +1,361 @@
+use std::fmt;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+
+
+// steps along a ray before giving up, and how close to the surface counts
+// as a hit
+const MAX_STEPS: usize = 512;
+const SURFACE: f64 = 1e-4;
+
+
+// distance functions, combined into trees. each returns the distance from
+// a point to its surface, negative inside, or a lower bound of it
+#[derive(Debug, Clone)]
+pub enum Distance {
+    Sphere { radius: f64 },
+    // box from -half to half
+    Box { half: Vec3 },
+    // the same box with its edges rounded off by `radius`
+    RoundedBox { half: Vec3, radius: f64 },
+    Translate { offset: Vec3, inner: Box<Distance> },
+    // union blending the two surfaces over a distance of about `k`
+    SmoothUnion { a: Box<Distance>, b: Box<Distance>, k: f64 },
+    // endless copies `period` apart, an axis with period 0 isn't repeated
+    Repeat { period: Vec3, inner: Box<Distance> },
+    // turns around the y axis by `rate` radians per unit of height
+    Twist { rate: f64, inner: Box<Distance> },
+    // power 8 is the classic bulb, it fits in a sphere of radius 1.2
+    Mandelbulb { power: f64, iterations: usize },
+    // sponge filling the box from -1 to 1
+    Menger { iterations: usize },
+}
+
+
+impl Distance {
+
+    pub fn translate(self, offset: Vec3) -> Self {
+        Distance::Translate { offset, inner: Box::new(self) }
+    }
+
+    pub fn smooth_union(self, other: Self, k: f64) -> Self {
+        Distance::SmoothUnion { a: Box::new(self), b: Box::new(other), k }
+    }
+
+    pub fn repeat(self, period: Vec3) -> Self {
+        Distance::Repeat { period, inner: Box::new(self) }
+    }
+
+    pub fn twist(self, rate: f64) -> Self {
+        Distance::Twist { rate, inner: Box::new(self) }
+    }
+
+    pub fn distance(&self, p: Vec3) -> f64 {
+        match self {
+            Distance::Sphere { radius } => p.len() - radius,
+            Distance::Box { half } => box_distance(p, *half),
+            Distance::RoundedBox { half, radius } => {
+                let inner = *half - Vec3::new(*radius, *radius, *radius);
+                box_distance(p, inner) - radius
+            },
+            Distance::Translate { offset, inner } => inner.distance(p - *offset),
+            Distance::SmoothUnion { a, b, k } => {
+                let (a, b) = (a.distance(p), b.distance(p));
+                let h = (k - (a - b).abs()).max(0.0) / k.max(1e-12);
+                a.min(b) - h * h * k / 4.0
+            },
+            Distance::Repeat { period, inner } => {
+                let wrap = |x: f64, period: f64| match period > 0.0 {
+                    true => x - period * (x / period).round(),
+                    false => x,
+                };
+                inner.distance(Vec3::new(
+                    wrap(p.x, period.x), wrap(p.y, period.y), wrap(p.z, period.z)
+                ))
+            },
+            Distance::Twist { rate, inner } => {
+                let (sin, cos) = (rate * p.y).sin_cos();
+                inner.distance(Vec3::new(cos * p.x - sin * p.z, p.y, sin * p.x + cos * p.z))
+            },
+            Distance::Mandelbulb { power, iterations } => mandelbulb(p, *power, *iterations),
+            Distance::Menger { iterations } => menger(p, *iterations),
+        }
+    }
+
+    // box around the surface, none for endless repetition
+    pub fn bounds(&self) -> Option<Aabb> {
+        match self {
+            Distance::Sphere { radius } => Some(centered(Vec3::new(*radius, *radius, *radius))),
+            Distance::Box { half } | Distance::RoundedBox { half, .. } => Some(centered(*half)),
+            Distance::Translate { offset, inner } => inner.bounds().map(|bbox| Aabb {
+                lower: bbox.lower + *offset,
+                upper: bbox.upper + *offset,
+            }),
+            // blending only ever adds up to k / 4 around the surfaces
+            Distance::SmoothUnion { a, b, k } => {
+                let grow = Vec3::new(k / 4.0, k / 4.0, k / 4.0);
+                let bbox = a.bounds()?.merge(b.bounds()?);
+                Some(Aabb { lower: bbox.lower - grow, upper: bbox.upper + grow })
+            },
+            Distance::Repeat { .. } => None,
+            // any turn stays within the circle around the y axis
+            Distance::Twist { inner, .. } => {
+                let bbox = inner.bounds()?;
+                let r = radial(&bbox);
+                Some(Aabb {
+                    lower: Vec3::new(-r, bbox.lower.y, -r),
+                    upper: Vec3::new(r, bbox.upper.y, r),
+                })
+            },
+            Distance::Mandelbulb { .. } => Some(centered(Vec3::new(1.2, 1.2, 1.2))),
+            Distance::Menger { .. } => Some(centered(Vec3::new(1.0, 1.0, 1.0))),
+        }
+    }
+
+    // how much faster than the true distance the function can change,
+    // steps are shortened by it. only twisting bends space enough to
+    // matter, the further from the axis the more
+    fn lipschitz(&self) -> f64 {
+        match self {
+            Distance::Translate { inner, .. } | Distance::Repeat { inner, .. } => {
+                inner.lipschitz()
+            },
+            Distance::SmoothUnion { a, b, .. } => a.lipschitz().max(b.lipschitz()),
+            Distance::Twist { rate, inner } => {
+                let r = inner.bounds().map(|bbox| radial(&bbox)).unwrap_or(1.0);
+                inner.lipschitz() * (1.0 + (rate * r).powi(2)).sqrt()
+            },
+            _ => 1.0,
+        }
+    }
+}
+
+
+fn centered(half: Vec3) -> Aabb {
+    Aabb { lower: -half, upper: half }
+}
+
+// furthest a box reaches from the y axis
+fn radial(bbox: &Aabb) -> f64 {
+    let x = bbox.lower.x.abs().max(bbox.upper.x.abs());
+    let z = bbox.lower.z.abs().max(bbox.upper.z.abs());
+    (x * x + z * z).sqrt()
+}
+
+fn box_distance(p: Vec3, half: Vec3) -> f64 {
+    let q = Vec3::new(p.x.abs() - half.x, p.y.abs() - half.y, p.z.abs() - half.z);
+    let outside = Vec3::new(q.x.max(0.0), q.y.max(0.0), q.z.max(0.0)).len();
+    outside + q.x.max(q.y).max(q.z).min(0.0)
+}
+
+// distance estimate from how fast the orbit of p escapes
+fn mandelbulb(p: Vec3, power: f64, iterations: usize) -> f64 {
+    let mut z = p;
+    let mut dr = 1.0;
+    let mut r = z.len();
+
+    for _ in 0..iterations {
+        if r > 2.0 { break }
+
+        dr = r.powf(power - 1.0) * power * dr + 1.0;
+        z = match r > 1e-12 {
+            true => {
+                let theta = (z.z / r).acos() * power;
+                let phi = z.y.atan2(z.x) * power;
+                let direction = Vec3::new(
+                    theta.sin() * phi.cos(), theta.sin() * phi.sin(), theta.cos()
+                );
+                r.powf(power) * direction + p
+            },
+            false => p,
+        };
+        r = z.len();
+    }
+
+    match r > 1e-12 {
+        true => 0.5 * r.ln() * r / dr,
+        false => 0.0,
+    }
+}
+
+// each level cuts the cross shaped holes out of every cube left, three
+// times smaller than the last
+fn menger(p: Vec3, iterations: usize) -> f64 {
+    let mut d = box_distance(p, Vec3::new(1.0, 1.0, 1.0));
+    let mut scale = 1.0;
+
+    for _ in 0..iterations {
+        let cell = |x: f64| (x * scale).rem_euclid(2.0) - 1.0;
+        let a = Vec3::new(cell(p.x), cell(p.y), cell(p.z));
+        scale *= 3.0;
+
+        let r = |a: f64| (1.0 - 3.0 * a.abs()).abs();
+        let r = Vec3::new(r(a.x), r(a.y), r(a.z));
+        let cross = r.x.max(r.y).min(r.y.max(r.z)).min(r.z.max(r.x));
+        d = d.max((cross - 1.0) / scale);
+    }
+    d
+}
+
+
+// surface given by a distance function, found by sphere tracing: the
+// distance at a point is a step that can't pass through the surface.
+// normals come from the change of the distance around the hit
+#[derive(Debug, Clone)]
+pub struct Sdf {
+    pub distance: Distance,
+    pub bbox: Aabb,
+    pub material: usize,
+    lipschitz: f64,
+}
+
+
+impl Sdf {
+
+    // bounded by the box worked out from the distance function
+    pub fn new(distance: Distance, material: usize) -> Object {
+        let bbox = distance
+            .bounds()
+            .expect("unbounded distance function, give the sdf a bounding box");
+        Self::bounded(distance, bbox, material)
+    }
+
+    // bounded by a given box, the surface is cut off at its sides
+    pub fn bounded(distance: Distance, bbox: Aabb, material: usize) -> Object {
+        let lipschitz = distance.lipschitz();
+        Self { distance, bbox, material, lipschitz }.into()
+    }
+
+    // gradient by central differences at the corners of a tetrahedron
+    fn normal(&self, p: Vec3) -> Vec3 {
+        let h = SURFACE;
+        let corners = [
+            Vec3::new(1.0, -1.0, -1.0), Vec3::new(-1.0, -1.0, 1.0),
+            Vec3::new(-1.0, 1.0, -1.0), Vec3::new(1.0, 1.0, 1.0),
+        ];
+        let gradient = corners
+            .iter()
+            .fold(Vec3::zero(), |sum, k| sum + *k * self.distance.distance(p + *k * h));
+        match gradient.near_zero() {
+            true => Vec3::new(0.0, 1.0, 0.0),
+            false => gradient.unit(),
+        }
+    }
+
+    fn contains(&self, p: Vec3) -> bool {
+        (0..3).all(|i| {
+            p[i] >= self.bbox.lower[i] - SURFACE && p[i] <= self.bbox.upper[i] + SURFACE
+        })
+    }
+}
+
+
+impl Intersect for Sdf {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let mut t = self.bbox.intersect(ray, t_min, t_max)?;
+        let speed = ray.direction().len();
+
+        for _ in 0..MAX_STEPS {
+            if t > t_max { return None }
+
+            let p = ray.at(t);
+            if !self.contains(p) { return None }
+
+            // rays leaving the inside march on the distance to the surface
+            // just the same
+            let d = self.distance.distance(p).abs();
+            if d < SURFACE {
+                return Some(
+                    IntersectResult::new(ray, t, self.normal(p), self.material)
+                )
+            }
+            t += d / (self.lipschitz * speed);
+        }
+        None
+    }
+
+    fn bbox(&self) -> Aabb {
+        self.bbox.clone()
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Sdf [ distance: {:?}, bbox: {:?} ]>", self.distance, self.bbox)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sdf_distances() {
+        let sphere = Distance::Sphere { radius: 1.0 };
+        assert!((sphere.distance(Vec3::new(3.0, 0.0, 0.0)) - 2.0).abs() < 1e-12);
+
+        let cube = Distance::Box { half: Vec3::new(1.0, 1.0, 1.0) };
+        assert!((cube.distance(Vec3::new(2.0, 2.0, 0.0)) - 2.0_f64.sqrt()).abs() < 1e-12);
+        assert!((cube.distance(Vec3::new(0.5, 0.0, 0.0)) + 0.5).abs() < 1e-12);
+
+        // rounding pulls the corner in, the faces stay put
+        let rounded = Distance::RoundedBox { half: Vec3::new(1.0, 1.0, 1.0), radius: 0.5 };
+        assert!(rounded.distance(Vec3::new(0.99, 0.99, 0.99)) > 0.0);
+        assert!(rounded.distance(Vec3::new(0.99, 0.0, 0.0)) < 0.0);
+
+        // smooth union fills in between two spheres
+        let pair = Distance::Sphere { radius: 1.0 }
+            .translate(Vec3::new(-1.1, 0.0, 0.0))
+            .smooth_union(Distance::Sphere { radius: 1.0 }.translate(Vec3::new(1.1, 0.0, 0.0)), 0.5);
+        assert!(pair.distance(Vec3::zero()) < 0.0);
+        assert!(pair.bounds().unwrap().upper.x > 2.1);
+
+        // copies every 4 along x, bounded by nothing
+        let row = Distance::Sphere { radius: 1.0 }.repeat(Vec3::new(4.0, 0.0, 0.0));
+        assert!((row.distance(Vec3::new(8.0, 2.0, 0.0)) - 1.0).abs() < 1e-12);
+        assert!(row.bounds().is_none());
+
+        // a twisted box still reaches as far out as its corners
+        let twisted = Distance::Box { half: Vec3::new(1.0, 2.0, 1.0) }.twist(1.0);
+        let bbox = twisted.bounds().unwrap();
+        assert!((bbox.upper.x - 2.0_f64.sqrt()).abs() < 1e-12 && bbox.upper.y == 2.0);
+        assert!(twisted.lipschitz() > 1.0);
+    }
+
+    #[test]
+    fn sdf_hits() {
+        let sphere = Sdf::new(Distance::Sphere { radius: 1.0 }, 0);
+        let ray = Ray::new(Vec3::new(-5.0, 0.3, 0.0), Vec3::new(2.0, 0.0, 0.0));
+        let hit = sphere.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        let expected = (5.0 - (1.0 - 0.09_f64).sqrt()) / 2.0;
+        assert!((hit.t - expected).abs() < 1e-4);
+        assert!((hit.normal - hit.point.unit()).len() < 1e-3);
+
+        // from inside out
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.0, 1.0, 0.0));
+        let hit = sphere.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 1.0).abs() < 1e-4 && !hit.front_face);
+
+        // the bulb is in the way along x, the sponge has a hole down its middle
+        let bulb = Sdf::new(Distance::Mandelbulb { power: 8.0, iterations: 12 }, 0);
+        let ray = Ray::new(Vec3::new(-3.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0));
+        let hit = bulb.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!(hit.t > 1.8 && hit.t < 3.0);
+
+        let sponge = Sdf::new(Distance::Menger { iterations: 3 }, 0);
+        let ray = Ray::new(Vec3::new(0.0, 0.0, -3.0), Vec3::new(0.0, 0.0, 1.0));
+        assert!(sponge.intersect(&ray, 0.001, f64::INFINITY).is_none());
+        let ray = Ray::new(Vec3::new(0.99, 0.99, -3.0), Vec3::new(0.0, 0.0, 1.0));
+        let hit = sponge.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!((hit.t - 2.0).abs() < 1e-3);
+    }
+}
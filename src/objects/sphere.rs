@@ -6,13 +6,28 @@ use crate::behaviors::{Intersect, IntersectResult};
 use crate::objects::{ Aabb, Object };
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Sphere {
     pub center: Vec3,
     pub radius: f64,
     pub material: usize,
 }
 
+impl Sphere {
+
+    fn result(&self, ray: &Ray, root: f64) -> IntersectResult {
+        let point = ray.at(root);
+        let outward_normal = (point - self.center).unit();
+        let (u, v) = sphere_uv(outward_normal);
+        let (dpdu, dpdv) = sphere_tangents(point - self.center);
+
+        IntersectResult::new(ray, root, outward_normal, self.material)
+            .with_uv(u, v)
+            .with_tangents(dpdu, dpdv)
+            .sampled()
+    }
+}
+
 impl Intersect for Sphere {
 
     fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64) 
@@ -39,11 +54,28 @@ impl Intersect for Sphere {
             }
         }
 
-        let point = ray.at(root);
-        let outward_normal = (point - self.center).unit();
-
         unsafe { crate::INTERSECT_PASSES_SP += 1; }
-        Some(IntersectResult::new(&ray, root, outward_normal, self.material))
+        Some(self.result(ray, root))
+    }
+
+    // both roots of the quadratic at once
+    fn intersect_all(&self, ray: &Ray, t_min: f64, t_max: f64) -> Vec<IntersectResult> {
+        let oc = ray.origin() - self.center;
+        let a = ray.direction().sq_len();
+        let half_b = oc.dot(ray.direction());
+        let c = oc.sq_len() - self.radius * self.radius;
+        let discriminant = half_b * half_b - a * c;
+
+        if discriminant < 0.0 {
+            return vec![]
+        }
+
+        let sqrtd = discriminant.sqrt();
+        [(-half_b - sqrtd) / a, (-half_b + sqrtd) / a]
+            .into_iter()
+            .filter(|root| *root >= t_min && *root <= t_max)
+            .map(|root| self.result(ray, root))
+            .collect()
     }
 
     fn bbox(&self) -> Aabb {
@@ -63,3 +95,27 @@ impl Intersect for Sphere {
     }
 
 }
+
+
+pub fn sphere_uv(p: Vec3) -> (f64, f64) {
+    // p is a point on the unit sphere, u goes around the y axis
+    // starting from -x, v goes from -y to +y
+    let theta = (-p.y).clamp(-1.0, 1.0).acos();
+    let phi = (-p.z).atan2(p.x) + std::f64::consts::PI;
+
+    (phi / (2.0 * std::f64::consts::PI), theta / std::f64::consts::PI)
+}
+
+
+pub fn sphere_tangents(p: Vec3) -> (Vec3, Vec3) {
+    // derivatives of the sphere_uv parametrization, p relative to center
+    let pi = std::f64::consts::PI;
+    let r_xz = (p.x * p.x + p.z * p.z).sqrt();
+
+    let dpdu = 2.0 * pi * Vec3::new(p.z, 0.0, -p.x);
+    let dpdv = match r_xz > 1e-8 {
+        true => pi * Vec3::new(-p.x * p.y / r_xz, r_xz, -p.z * p.y / r_xz),
+        false => Vec3::zero(),
+    };
+    (dpdu, dpdv)
+}
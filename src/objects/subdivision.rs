@@ -0,0 +1,548 @@
+use std::collections::{ HashMap, HashSet };
+
+use crate::Vec3;
+use crate::objects::Mesh;
+
+
+// faces meeting at more than this many degrees keep a hard edge
+pub const DEFAULT_CREASE_ANGLE: f64 = 30.0;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scheme {
+    // any polygons, every level turns them into quads
+    CatmullClark,
+    // triangles, other polygons are fanned into triangles first
+    Loop,
+}
+
+
+#[derive(Debug, Clone, Copy)]
+pub struct Subdivision {
+    pub scheme: Scheme,
+    pub levels: usize,
+    pub crease_angle: f64,
+}
+
+
+type Edge = (usize, usize);
+
+fn edge(a: usize, b: usize) -> Edge {
+    (a.min(b), a.max(b))
+}
+
+
+// polygons sharing their vertices, what the subdivision rules work on.
+// texcoords belong to face corners and are interpolated linearly on every
+// face, so seams stay where they are. sharp edges are creases, they are
+// split in two on every level and stay sharp
+#[derive(Debug, Clone)]
+struct Cage {
+    vertices: Vec<Vec3>,
+    faces: Vec<Vec<usize>>,
+    uvs: Vec<(f64, f64)>,
+    face_uvs: Vec<Vec<usize>>,
+    sharp: HashSet<Edge>,
+}
+
+
+impl Cage {
+
+    // the polygons the mesh was loaded from if it remembers them,
+    // its triangles otherwise
+    fn from_mesh(mesh: &Mesh) -> Self {
+        let has_uvs = !mesh.texcoords.is_empty();
+
+        let (faces, face_uvs): (Vec<Vec<usize>>, Vec<Vec<usize>>) = match mesh.polygons.is_empty() {
+            true => (0..mesh.index_amt)
+                .step_by(3)
+                .map(|i| {
+                    let face = (i..i + 3).map(|k| mesh.indexes[k].0).collect();
+                    let uvs = match has_uvs {
+                        true => (i..i + 3).map(|k| mesh.texcoord_indexes[k]).collect(),
+                        false => vec![],
+                    };
+                    (face, uvs)
+                })
+                .unzip(),
+            false => mesh.polygons
+                .iter()
+                .map(|polygon| {
+                    let face = polygon.iter().map(|corner| corner.0).collect();
+                    let uvs = match has_uvs {
+                        true => polygon.iter().map(|corner| corner.1).collect(),
+                        false => vec![],
+                    };
+                    (face, uvs)
+                })
+                .unzip(),
+        };
+
+        Self {
+            vertices: mesh.vertices.clone(),
+            faces,
+            uvs: mesh.texcoords.clone(),
+            face_uvs: match has_uvs { true => face_uvs, false => vec![] },
+            sharp: HashSet::new(),
+        }
+    }
+
+    // twice the area along the normal, by newell's method so it holds for
+    // polygons that aren't flat
+    fn face_normal(&self, face: &[usize]) -> Vec3 {
+        (0..face.len()).fold(Vec3::zero(), |sum, i| {
+            let (a, b) = (self.vertices[face[i]], self.vertices[face[(i + 1) % face.len()]]);
+            sum + a.cross(b)
+        })
+    }
+
+    fn edge_faces(&self) -> HashMap<Edge, Vec<usize>> {
+        let mut edges: HashMap<Edge, Vec<usize>> = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            for i in 0..face.len() {
+                edges.entry(edge(face[i], face[(i + 1) % face.len()])).or_default().push(f);
+            }
+        }
+        edges
+    }
+
+    fn neighbors(&self, edges: &HashMap<Edge, Vec<usize>>) -> Vec<Vec<usize>> {
+        let mut neighbors = vec![vec![]; self.vertices.len()];
+        for &(a, b) in edges.keys() {
+            neighbors[a].push(b);
+            neighbors[b].push(a);
+        }
+        neighbors
+    }
+
+    // edges between faces bent further than the crease angle are sharp,
+    // so are boundaries and edges shared by more than two faces
+    fn mark_creases(&mut self, crease_angle: f64) {
+        let normals: Vec<Vec3> = self.faces
+            .iter()
+            .map(|face| {
+                let normal = self.face_normal(face);
+                if normal.near_zero() { normal } else { normal.unit() }
+            })
+            .collect();
+        let cos_max = crease_angle.to_radians().cos();
+
+        for (e, faces) in self.edge_faces() {
+            let sharp = match faces.as_slice() {
+                [f, g] => normals[*f].dot(normals[*g]) < cos_max,
+                _ => true,
+            };
+            if sharp { self.sharp.insert(e); }
+        }
+    }
+
+    // a vertex on one crease slides along it, where more creases meet it
+    // is a corner and stays put. none for smooth vertices
+    fn crease_rule(&self, v: usize, neighbors: &[usize]) -> Option<Vec3> {
+        let sharp: Vec<usize> = neighbors
+            .iter()
+            .filter(|w| self.sharp.contains(&edge(v, **w)))
+            .copied()
+            .collect();
+        let p = self.vertices[v];
+
+        match sharp.len() {
+            0 | 1 => None,
+            2 => Some((6.0 * p + self.vertices[sharp[0]] + self.vertices[sharp[1]]) / 8.0),
+            _ => Some(p),
+        }
+    }
+
+    fn corner_uv(&self, f: usize, i: usize) -> (f64, f64) {
+        self.uvs[self.face_uvs[f][i]]
+    }
+
+    fn push_uv(uvs: &mut Vec<(f64, f64)>, points: &[(f64, f64)]) -> usize {
+        let n = points.len() as f64;
+        let sum = points.iter().fold((0.0, 0.0), |sum, uv| (sum.0 + uv.0, sum.1 + uv.1));
+        uvs.push((sum.0 / n, sum.1 / n));
+        uvs.len() - 1
+    }
+
+    // both halves of every split crease stay sharp
+    fn split_creases(&self, edge_points: &HashMap<Edge, usize>) -> HashSet<Edge> {
+        self.sharp
+            .iter()
+            .flat_map(|&(a, b)| {
+                let middle = edge_points[&(a, b)];
+                [edge(a, middle), edge(middle, b)]
+            })
+            .collect()
+    }
+
+    // a point in the middle of every face and edge, every face becomes a
+    // quad per corner. new vertices go after the moved old ones, first the
+    // face points and then the edge points
+    fn catmull_clark(&self) -> Cage {
+        let edges = self.edge_faces();
+        let neighbors = self.neighbors(&edges);
+        let (nv, nf) = (self.vertices.len(), self.faces.len());
+
+        let face_points: Vec<Vec3> = self.faces
+            .iter()
+            .map(|face| {
+                face.iter().fold(Vec3::zero(), |sum, v| sum + self.vertices[*v]) / face.len() as f64
+            })
+            .collect();
+
+        let mut vertex_faces = vec![vec![]; nv];
+        for (f, face) in self.faces.iter().enumerate() {
+            for v in face {
+                vertex_faces[*v].push(f);
+            }
+        }
+
+        let mut vertices: Vec<Vec3> = (0..nv)
+            .map(|v| {
+                if let Some(p) = self.crease_rule(v, &neighbors[v]) { return p }
+
+                let p = self.vertices[v];
+                let n = vertex_faces[v].len();
+                if n == 0 || neighbors[v].is_empty() { return p }
+
+                let f = vertex_faces[v].iter().fold(Vec3::zero(), |sum, f| sum + face_points[*f])
+                    / n as f64;
+                let r = neighbors[v].iter().fold(Vec3::zero(), |sum, w| sum + self.vertices[*w])
+                    / neighbors[v].len() as f64;
+                let r = (p + r) / 2.0;
+                (f + 2.0 * r + (n as f64 - 3.0) * p) / n as f64
+            })
+            .collect();
+        vertices.extend(&face_points);
+
+        let mut edge_points: HashMap<Edge, usize> = HashMap::new();
+        for (&(a, b), faces) in edges.iter() {
+            let middle = (self.vertices[a] + self.vertices[b]) / 2.0;
+            let point = match (self.sharp.contains(&(a, b)), faces.as_slice()) {
+                (false, [f, g]) => (middle + (face_points[*f] + face_points[*g]) / 2.0) / 2.0,
+                _ => middle,
+            };
+            edge_points.insert((a, b), vertices.len());
+            vertices.push(point);
+        }
+
+        let has_uvs = !self.face_uvs.is_empty();
+        let mut uvs = self.uvs.clone();
+        let mut faces = vec![];
+        let mut face_uvs = vec![];
+
+        for (f, face) in self.faces.iter().enumerate() {
+            let k = face.len();
+            let e = |i: usize| edge_points[&edge(face[i % k], face[(i + 1) % k])];
+
+            for (i, v) in face.iter().enumerate() {
+                faces.push(vec![*v, e(i), nv + f, e(i + k - 1)]);
+            }
+
+            if has_uvs {
+                let corners: Vec<(f64, f64)> = (0..k).map(|i| self.corner_uv(f, i)).collect();
+                let center = Cage::push_uv(&mut uvs, &corners);
+                let middles: Vec<usize> = (0..k)
+                    .map(|i| Cage::push_uv(&mut uvs, &[corners[i], corners[(i + 1) % k]]))
+                    .collect();
+                for i in 0..k {
+                    face_uvs.push(vec![
+                        self.face_uvs[f][i], middles[i], center, middles[(i + k - 1) % k],
+                    ]);
+                }
+            }
+        }
+        debug_assert_eq!(vertices.len(), nv + nf + edges.len());
+
+        Cage { vertices, faces, uvs, face_uvs, sharp: self.split_creases(&edge_points) }
+    }
+
+    // every polygon fanned into triangles from its first corner
+    fn triangulated(&self) -> Cage {
+        let mut faces = vec![];
+        let mut face_uvs = vec![];
+        for (f, face) in self.faces.iter().enumerate() {
+            for i in 1..face.len().saturating_sub(1) {
+                faces.push(vec![face[0], face[i], face[i + 1]]);
+                if !self.face_uvs.is_empty() {
+                    let uv = &self.face_uvs[f];
+                    face_uvs.push(vec![uv[0], uv[i], uv[i + 1]]);
+                }
+            }
+        }
+        Cage { faces, face_uvs, ..self.clone() }
+    }
+
+    // a point on every edge, every triangle becomes four. new vertices go
+    // after the moved old ones
+    fn loop_subdivide(&self) -> Cage {
+        let edges = self.edge_faces();
+        let neighbors = self.neighbors(&edges);
+        let nv = self.vertices.len();
+
+        let mut vertices: Vec<Vec3> = (0..nv)
+            .map(|v| {
+                if let Some(p) = self.crease_rule(v, &neighbors[v]) { return p }
+
+                let p = self.vertices[v];
+                let n = neighbors[v].len();
+                if n == 0 { return p }
+
+                let beta = if n == 3 { 3.0 / 16.0 } else { 3.0 / (8.0 * n as f64) };
+                let sum = neighbors[v].iter().fold(Vec3::zero(), |sum, w| sum + self.vertices[*w]);
+                (1.0 - n as f64 * beta) * p + beta * sum
+            })
+            .collect();
+
+        let opposite = |f: usize, (a, b): Edge| {
+            let corner = self.faces[f].iter().find(|v| **v != a && **v != b);
+            self.vertices[*corner.expect("degenerate triangle")]
+        };
+
+        let mut edge_points: HashMap<Edge, usize> = HashMap::new();
+        for (&(a, b), faces) in edges.iter() {
+            let ends = self.vertices[a] + self.vertices[b];
+            let point = match (self.sharp.contains(&(a, b)), faces.as_slice()) {
+                (false, [f, g]) => {
+                    3.0 / 8.0 * ends + (opposite(*f, (a, b)) + opposite(*g, (a, b))) / 8.0
+                },
+                _ => ends / 2.0,
+            };
+            edge_points.insert((a, b), vertices.len());
+            vertices.push(point);
+        }
+
+        let has_uvs = !self.face_uvs.is_empty();
+        let mut uvs = self.uvs.clone();
+        let mut faces = vec![];
+        let mut face_uvs = vec![];
+
+        for (f, face) in self.faces.iter().enumerate() {
+            let [a, b, c] = [face[0], face[1], face[2]];
+            let (ab, bc, ca) = (
+                edge_points[&edge(a, b)], edge_points[&edge(b, c)], edge_points[&edge(c, a)]
+            );
+            faces.extend([vec![a, ab, ca], vec![b, bc, ab], vec![c, ca, bc], vec![ab, bc, ca]]);
+
+            if has_uvs {
+                let [ua, ub, uc] = [self.face_uvs[f][0], self.face_uvs[f][1], self.face_uvs[f][2]];
+                let [pa, pb, pc] = [self.uvs[ua], self.uvs[ub], self.uvs[uc]];
+                let uab = Cage::push_uv(&mut uvs, &[pa, pb]);
+                let ubc = Cage::push_uv(&mut uvs, &[pb, pc]);
+                let uca = Cage::push_uv(&mut uvs, &[pc, pa]);
+                face_uvs.extend([
+                    vec![ua, uab, uca], vec![ub, ubc, uab], vec![uc, uca, ubc], vec![uab, ubc, uca],
+                ]);
+            }
+        }
+
+        Cage { vertices, faces, uvs, face_uvs, sharp: self.split_creases(&edge_points) }
+    }
+
+    // triangles for the mesh, with a normal per group of corners around a
+    // vertex that aren't split by a crease. each group averages the
+    // normals of its faces weighted by area, so creases stay hard
+    fn into_mesh(self, mesh: &Mesh) -> Mesh {
+        let mut first_corner = Vec::with_capacity(self.faces.len());
+        let mut corners = 0;
+        for face in self.faces.iter() {
+            first_corner.push(corners);
+            corners += face.len();
+        }
+        let corner = |f: usize, v: usize| {
+            first_corner[f] + self.faces[f].iter().position(|w| *w == v).unwrap()
+        };
+
+        let mut parent: Vec<usize> = (0..corners).collect();
+        fn find(parent: &mut [usize], mut i: usize) -> usize {
+            while parent[i] != i {
+                parent[i] = parent[parent[i]];
+                i = parent[i];
+            }
+            i
+        }
+
+        for (e, faces) in self.edge_faces() {
+            if let (false, [f, g]) = (self.sharp.contains(&e), faces.as_slice()) {
+                for v in [e.0, e.1] {
+                    let (a, b) = (find(&mut parent, corner(*f, v)), find(&mut parent, corner(*g, v)));
+                    parent[a] = b;
+                }
+            }
+        }
+
+        let mut sums: HashMap<usize, Vec3> = HashMap::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let normal = self.face_normal(face);
+            for i in 0..face.len() {
+                let root = find(&mut parent, first_corner[f] + i);
+                *sums.entry(root).or_insert(Vec3::zero()) += normal;
+            }
+        }
+
+        let mut normals = vec![];
+        let mut normal_index: HashMap<usize, usize> = HashMap::new();
+        let mut indexes = vec![];
+        let mut texcoord_indexes = vec![];
+
+        for (f, face) in self.faces.iter().enumerate() {
+            for i in 1..face.len().saturating_sub(1) {
+                for k in [0, i, i + 1] {
+                    let root = find(&mut parent, first_corner[f] + k);
+                    let n = *normal_index.entry(root).or_insert_with(|| {
+                        let sum = sums[&root];
+                        normals.push(if sum.near_zero() { sum } else { sum.unit() });
+                        normals.len() - 1
+                    });
+                    indexes.push((face[k], n));
+                    if !self.face_uvs.is_empty() {
+                        texcoord_indexes.push(self.face_uvs[f][k]);
+                    }
+                }
+            }
+        }
+
+        let mut subdivided = Mesh::new(self.vertices, indexes, normals, mesh.material);
+        subdivided.double_sided = mesh.double_sided;
+        match texcoord_indexes.is_empty() {
+            true => subdivided,
+            false => subdivided.with_texcoords(self.uvs, texcoord_indexes),
+        }
+    }
+}
+
+
+// the mesh refined by a subdivision scheme, as triangles with smooth
+// normals except along its creases
+pub fn subdivide(mesh: &Mesh, subdivision: Subdivision) -> Mesh {
+    let mut cage = Cage::from_mesh(mesh);
+    cage.mark_creases(subdivision.crease_angle);
+
+    if subdivision.scheme == Scheme::Loop {
+        cage = cage.triangulated();
+    }
+    for _ in 0..subdivision.levels {
+        cage = match subdivision.scheme {
+            Scheme::CatmullClark => cage.catmull_clark(),
+            Scheme::Loop => cage.loop_subdivide(),
+        };
+    }
+
+    cage.into_mesh(mesh)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::helpers;
+
+    // cube from -1 to 1 as six quads
+    fn cube() -> Mesh {
+        let corners = helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0).vertices;
+        helpers::from_polygons(corners, vec![
+            vec![0, 1, 2, 3], vec![5, 4, 7, 6], vec![1, 5, 6, 2],
+            vec![4, 0, 3, 7], vec![3, 2, 6, 7], vec![4, 5, 1, 0],
+        ], 0)
+    }
+
+    fn subdivision(scheme: Scheme, levels: usize, crease_angle: f64) -> Subdivision {
+        Subdivision { scheme, levels, crease_angle }
+    }
+
+    #[test]
+    fn catmull_clark_cube() {
+        // smooth, the corners are pulled in to 5/9
+        let smooth = subdivide(&cube(), subdivision(Scheme::CatmullClark, 1, 180.0));
+        assert_eq!(smooth.vertices.len(), 8 + 6 + 12);
+        assert_eq!(smooth.index_amt, 6 * 4 * 2 * 3);
+        let corner = smooth.vertices[6];
+        assert!((corner - Vec3::new(5.0, 5.0, -5.0) / 9.0).len() < 1e-12);
+        // rounds towards a sphere with one smooth normal per vertex
+        let smoother = subdivide(&cube(), subdivision(Scheme::CatmullClark, 3, 180.0));
+        assert_eq!(smoother.normals.len(), smoother.vertices.len());
+
+        // every edge of the cube is a crease, it keeps its shape and its
+        // normals stay square to the faces
+        let creased = subdivide(&cube(), subdivision(Scheme::CatmullClark, 2, 30.0));
+        for v in creased.vertices.iter() {
+            assert!((v.x.abs().max(v.y.abs()).max(v.z.abs()) - 1.0).abs() < 1e-12);
+        }
+        for n in creased.normals.iter() {
+            assert!((n.x.abs().max(n.y.abs()).max(n.z.abs()) - 1.0).abs() < 1e-12);
+        }
+
+        // the triangulated cube is bent only along its real edges
+        let triangles = helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0);
+        let creased = subdivide(&triangles, subdivision(Scheme::CatmullClark, 1, 30.0));
+        assert!(creased.vertices.iter().all(|v| v.x.abs().max(v.y.abs()).max(v.z.abs()) > 0.999));
+    }
+
+    #[test]
+    fn loop_rules() {
+        // a lone triangle is all boundary, its corners slide along it
+        let triangle = Mesh::new(
+            vec![Vec3::zero(), Vec3::new(8.0, 0.0, 0.0), Vec3::new(0.0, 8.0, 0.0)],
+            vec![(0, 0), (1, 0), (2, 0)],
+            vec![],
+            0,
+        );
+        let refined = subdivide(&triangle, subdivision(Scheme::Loop, 1, 30.0));
+        assert_eq!(refined.index_amt, 4 * 3);
+        assert!((refined.vertices[0] - Vec3::new(1.0, 1.0, 0.0)).len() < 1e-12);
+        assert!(refined.normals.iter().all(|n| (*n - Vec3::new(0.0, 0.0, 1.0)).len() < 1e-12));
+
+        // octahedron, every vertex has four neighbors and pulls in by
+        // 1 - 4 * 3 / 32, every edge point sits at 3/8 + 3/8 + 1/8 + 1/8
+        let vertices = vec![
+            Vec3::new(1.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0),
+            Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, -1.0, 0.0),
+            Vec3::new(0.0, 0.0, 1.0), Vec3::new(0.0, 0.0, -1.0),
+        ];
+        let faces = [
+            [0, 2, 4], [2, 1, 4], [1, 3, 4], [3, 0, 4],
+            [2, 0, 5], [1, 2, 5], [3, 1, 5], [0, 3, 5],
+        ];
+        let indexes = faces.iter().flat_map(|face| face.map(|v| (v, 0))).collect();
+        let octahedron = Mesh::new(vertices, indexes, vec![], 0);
+        let refined = subdivide(&octahedron, subdivision(Scheme::Loop, 1, 180.0));
+        assert!((refined.vertices[0] - Vec3::new(0.625, 0.0, 0.0)).len() < 1e-12);
+        assert!(refined.vertices[6..].iter().all(|v| (v.len() - 0.75 / 2.0_f64.sqrt()).abs() < 1e-12));
+
+        // quads are fanned into triangles first
+        let refined = subdivide(&cube(), subdivision(Scheme::Loop, 1, 30.0));
+        assert_eq!(refined.index_amt, 12 * 4 * 3);
+    }
+
+    // quads of an obj file keep their hard edges, the open rim at the
+    // bottom is a boundary and stays in its plane even when smoothed
+    #[test]
+    fn obj_quads_creases() {
+        let obj = "v 0 0 0\nv 1 0 0\nv 1 0 1\nv 0 0 1\nv 0 1 0\nv 1 1 0\nv 1 1 1\nv 0 1 1\n\
+            vn 0 1 0\n\
+            f 4//1 3//1 7//1 8//1\nf 3//1 2//1 6//1 7//1\nf 2//1 1//1 5//1 6//1\n\
+            f 1//1 4//1 8//1 5//1\nf 8//1 7//1 6//1 5//1\n";
+        let path = std::env::temp_dir().join("subdivision_open_box.obj");
+        std::fs::write(&path, obj).unwrap();
+        let open_box = helpers::from_obj(path.to_string_lossy().into_owned(), 0);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(open_box.polygons.len(), 5);
+        assert_eq!(open_box.index_amt, 5 * 2 * 3);
+
+        let on_box = |v: &Vec3| {
+            let inside = (0..3).all(|i| v[i] > -1e-12 && v[i] < 1.0 + 1e-12);
+            let on_face = (0..3).any(|i| v[i].abs() < 1e-12 || (v[i] - 1.0).abs() < 1e-12);
+            inside && on_face
+        };
+        let hard = subdivision(Scheme::CatmullClark, 2, DEFAULT_CREASE_ANGLE);
+        let creased = subdivide(&open_box, hard);
+        assert_eq!(creased.index_amt, 5 * 16 * 2 * 3);
+        assert!(creased.vertices.iter().all(on_box));
+
+        let smooth = subdivide(&open_box, subdivision(Scheme::CatmullClark, 2, 180.0));
+        assert!(!smooth.vertices.iter().all(on_box));
+        let rim: Vec<&Vec3> = smooth.vertices.iter().filter(|v| v.y.abs() < 1e-12).collect();
+        assert_eq!(rim.len(), 4 * 4);
+        assert!(smooth.vertices.iter().all(|v| v.y > -1e-12));
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{ Ray, Matrix4 };
+use crate::behaviors::{ Intersect, IntersectResult };
+use crate::objects::{ Object, BvhNode, Instance, Prototype };
+
+
+// two level acceleration structure. every mesh and the loose primitives
+// of the world get their own bottom level bvh as a prototype, the top
+// level bvh only holds the instances placing them. moving instances
+// around only needs the small top level to be rebuilt
+#[derive(Debug)]
+pub struct Tlas {
+    // in the order they were given, so they can be found again to move them
+    pub instances: Vec<Instance>,
+    // the top level bvh over copies of the instances
+    pub root: usize,
+    pub objects: Vec<Object>,
+    pub nodes: Vec<BvhNode>,
+}
+
+
+impl Tlas {
+
+    pub fn new(world: Vec<Object>) -> Self {
+        let mut instances: Vec<Instance> = vec![];
+        let mut loose: Vec<Object> = vec![];
+
+        for object in world.into_iter() {
+            match object {
+                Object::Instance(instance) => instances.push(instance),
+                object => match object.divide() {
+                    Some(primitives) => instances.push(
+                        Instance::placed(&Prototype::new(primitives), Matrix4::identity(), None)
+                    ),
+                    None => loose.push(object),
+                },
+            }
+        }
+
+        if !loose.is_empty() {
+            instances.push(
+                Instance::placed(&Prototype::new(loose), Matrix4::identity(), None)
+            );
+        }
+
+        let mut tlas = Self { instances, root: 0, objects: vec![], nodes: vec![] };
+        tlas.rebuild();
+        tlas
+    }
+
+    // moves an instance, the top level has to be rebuilt afterwards
+    #[allow(dead_code)]
+    pub fn place(&mut self, index: usize, transform: Matrix4) {
+        let instance = &self.instances[index];
+        self.instances[index] = Instance::placed(
+            &instance.prototype, transform, instance.material
+        );
+    }
+
+    // only bounds of instances are sorted, the bottom levels are untouched
+    pub fn rebuild(&mut self) {
+        let top: Vec<Object> = self.instances
+            .iter()
+            .cloned()
+            .map(Object::from)
+            .collect();
+
+        self.objects.clear();
+        self.nodes.clear();
+        self.root = BvhNode::construct(top, &mut self.objects, &mut self.nodes);
+    }
+
+    pub fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        debug_assert!(self.root < self.nodes.len());
+        let node = unsafe { self.nodes.get_unchecked(self.root) };
+        node.intersect(ray, t_min, t_max, &self.objects, &self.nodes)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::Vec3;
+    use crate::objects::{ Sphere, Sealed, helpers };
+    use crate::materials::{ Material, Mix, Subsurface, Lambertian };
+
+    #[test]
+    fn tlas_place_and_rebuild() {
+        let ball = Prototype::new(vec![
+            Sphere { center: Vec3::zero(), radius: 1.0, material: 0 }.into(),
+        ]);
+        let mut tlas = Tlas::new(vec![
+            Instance::new(&ball, Matrix4::translate(-5.0, 0.0, 0.0)),
+            Instance::with_material(&ball, Matrix4::translate(5.0, 0.0, 0.0), 1),
+        ]);
+
+        let ray = Ray::new(Vec3::new(0.0, 0.0, 10.0), Vec3::new(0.0, 0.0, -1.0));
+        assert!(tlas.intersect(&ray, 0.001, f64::INFINITY).is_none());
+
+        // moved onto the ray, stretched along it, material override kept
+        tlas.place(1, Matrix4::scale(1.0, 1.0, 2.0));
+        tlas.rebuild();
+        let result = tlas.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!((result.t - 8.0).abs() < 1e-9);
+        assert!((result.normal - Vec3::new(0.0, 0.0, 1.0)).len() < 1e-9);
+        assert_eq!(result.material, 1);
+    }
+
+    // the random walk inside a subsurface mesh needs its back faces to
+    // find where it leaves
+    #[test]
+    fn tlas_sealed_mesh_exit() {
+        let materials: Vec<Material> = vec![Subsurface::jade()];
+        let ray = Ray::new(Vec3::zero(), Vec3::new(0.3, 0.2, 1.0));
+        let cube = || -> Object {
+            helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0).into()
+        };
+
+        assert!(Tlas::new(vec![cube()]).intersect(&ray, 0.001, f64::INFINITY).is_none());
+
+        let mut sealed = cube();
+        sealed.seal(&materials, None, &mut Sealed::new());
+        let result = Tlas::new(vec![sealed]).intersect(&ray, 0.001, f64::INFINITY).unwrap();
+        assert!(!result.front_face);
+        assert!((result.point.z - 1.0).abs() < 1e-9);
+        assert!(result.normal.z < 0.0);
+    }
+
+    // instances are sealed through their prototype, with the material of
+    // the instance when it overrides it and through mixes
+    #[test]
+    fn tlas_sealed_instance_exit() {
+        let materials: Vec<Material> = vec![
+            Lambertian::grey(),
+            Subsurface::jade(),
+            Mix::new(0, 1, 0.5),
+        ];
+        let ray = Ray::new(Vec3::new(10.0, 0.0, 0.0), Vec3::new(0.3, 0.2, 1.0));
+        let cube = Prototype::new(vec![
+            helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 0).into(),
+        ]);
+        let place = Matrix4::translate(10.0, 0.0, 0.0);
+
+        let mut sealed = Sealed::new();
+        let mut plain = Instance::new(&cube, place);
+        assert!(!plain.seal(&materials, None, &mut sealed));
+        assert!(Tlas::new(vec![plain]).intersect(&ray, 0.001, f64::INFINITY).is_none());
+
+        // each override seals its own copy of the prototype
+        let mut jade = Instance::with_material(&cube, place, 1);
+        let mut mixed = Instance::with_material(&cube, place, 2);
+        assert!(jade.seal(&materials, None, &mut sealed));
+        assert!(mixed.seal(&materials, None, &mut sealed));
+        let meshes = [&jade, &mixed].map(|object| match object {
+            Object::Instance(instance) => match &instance.prototype.objects[0] {
+                Object::Triangle(triangle) => Arc::clone(&triangle.mesh),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        });
+        assert!(meshes[0].double_sided && meshes[1].double_sided);
+        assert!(!Arc::ptr_eq(&meshes[0], &meshes[1]));
+
+        // and instances sharing an override share the copy
+        let mut again = Instance::with_material(&cube, Matrix4::identity(), 1);
+        again.seal(&materials, None, &mut sealed);
+        let prototype = |object: &Object| match object {
+            Object::Instance(instance) => Arc::clone(&instance.prototype),
+            _ => unreachable!(),
+        };
+        assert!(Arc::ptr_eq(&prototype(&jade), &prototype(&again)));
+
+        for instance in [jade, mixed] {
+            let result = Tlas::new(vec![instance]).intersect(&ray, 0.001, f64::INFINITY).unwrap();
+            assert!(!result.front_face);
+            assert!((result.point.z - 1.0).abs() < 1e-9);
+        }
+
+        // the prototype itself is left as it was
+        match &cube.objects[0] {
+            Object::Triangle(triangle) => assert!(!triangle.mesh.double_sided),
+            _ => unreachable!(),
+        }
+    }
+}
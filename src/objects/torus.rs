@@ -0,0 +1,270 @@
+use std::fmt;
+use std::f64::consts::PI;
+
+use crate::Vec3;
+use crate::Ray;
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+use crate::materials::microfacet::Frame;
+
+use rand::Rng;
+
+
+// ring around `axis` through `center`, a tube of radius `minor` swept
+// along a circle of radius `major`. u goes around the axis, v around the
+// tube starting from the outside
+#[derive(Debug, Clone)]
+pub struct Torus {
+    pub center: Vec3,
+    pub axis: Vec3,
+    pub major: f64,
+    pub minor: f64,
+    frame: Frame,
+    pub material: usize,
+}
+
+
+impl Torus {
+
+    pub fn new(
+        center: Vec3, axis: Vec3, major: f64, minor: f64, material: usize
+    ) -> Object {
+        let axis = axis.unit();
+        Self { center, axis, major, minor, frame: Frame::new(axis), material }.into()
+    }
+
+    pub fn area(&self) -> f64 {
+        4.0 * PI * PI * self.major * self.minor
+    }
+
+    // point and normal picked uniformly by area. the outside of the ring
+    // is larger than the inside, angles around the tube are kept by how
+    // far they are from the axis
+    pub fn sample(&self) -> (Vec3, Vec3) {
+        let mut rng = rand::thread_rng();
+        let phi = 2.0 * PI * rng.gen::<f64>();
+        let theta = loop {
+            let theta = 2.0 * PI * rng.gen::<f64>();
+            let keep = (self.major + self.minor * theta.cos()) / (self.major + self.minor);
+            if rng.gen::<f64>() < keep { break theta }
+        };
+
+        let (sin_phi, cos_phi) = phi.sin_cos();
+        let normal = Vec3::new(theta.cos() * cos_phi, theta.cos() * sin_phi, theta.sin());
+        let ring = Vec3::new(self.major * cos_phi, self.major * sin_phi, 0.0);
+        let local = ring + self.minor * normal;
+
+        (self.center + self.frame.to_world(local), self.frame.to_world(normal))
+    }
+}
+
+
+impl Intersect for Torus {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        let o = self.frame.to_local(ray.origin() - self.center);
+        let d = self.frame.to_local(ray.direction());
+        let (major, minor) = (self.major, self.minor);
+
+        // solving from the point of the ray closest to the center keeps
+        // the coefficients small for far away rays
+        let shift = -o.dot(d) / d.dot(d);
+        let o = o + shift * d;
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + y^2) along the ray
+        let a = d.dot(d);
+        let b = 2.0 * o.dot(d);
+        let c = o.dot(o) + major * major - minor * minor;
+        let r2 = 4.0 * major * major;
+        let coefficients = [
+            a * a,
+            2.0 * a * b,
+            b * b + 2.0 * a * c - r2 * (d.x * d.x + d.y * d.y),
+            2.0 * b * c - 2.0 * r2 * (o.x * d.x + o.y * d.y),
+            c * c - r2 * (o.x * o.x + o.y * o.y),
+        ];
+
+        let s = solve_quartic(coefficients)
+            .into_iter()
+            .filter(|s| s + shift >= t_min && s + shift <= t_max)
+            .fold(f64::INFINITY, f64::min);
+        if s == f64::INFINITY { return None }
+
+        // outward from the closest point of the ring
+        let p = o + s * d;
+        let rho = (p.x * p.x + p.y * p.y).sqrt();
+        let normal = match rho > 1e-12 {
+            true => (p - Vec3::new(p.x, p.y, 0.0) * (major / rho)).unit(),
+            false => Vec3::new(0.0, 0.0, p.z.signum()),
+        };
+
+        let phi = p.y.atan2(p.x).rem_euclid(2.0 * PI);
+        let theta = p.z.atan2(rho - major).rem_euclid(2.0 * PI);
+        let dpdu = 2.0 * PI * Vec3::new(-p.y, p.x, 0.0);
+        let dpdv = match rho > 1e-12 {
+            true => 2.0 * PI * Vec3::new(-p.z * p.x / rho, -p.z * p.y / rho, rho - major),
+            false => Vec3::zero(),
+        };
+
+        Some(
+            IntersectResult::new(ray, s + shift, self.frame.to_world(normal), self.material)
+                .with_uv(phi / (2.0 * PI), theta / (2.0 * PI))
+                .with_tangents(self.frame.to_world(dpdu), self.frame.to_world(dpdv))
+                .sampled()
+        )
+    }
+
+    // the ring bounded like a disk, grown by the tube in every direction
+    fn bbox(&self) -> Aabb {
+        let extent = |n: f64| self.major * (1.0 - n * n).max(0.0).sqrt() + self.minor;
+        let extent = Vec3::new(extent(self.axis.x), extent(self.axis.y), extent(self.axis.z));
+        Aabb { lower: self.center - extent, upper: self.center + extent }
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        None
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "<Torus [ center: {:?}, axis: {:?}, major: {}, minor: {} ]>",
+            self.center, self.axis, self.major, self.minor
+        )
+    }
+}
+
+
+// real roots of c[0] x^4 + c[1] x^3 + c[2] x^2 + c[3] x + c[4] by ferrari's
+// method, polished with a few newton steps since the closed form loses
+// precision
+fn solve_quartic(c: [f64; 5]) -> Vec<f64> {
+    let (a, b, cc, d) = (c[1] / c[0], c[2] / c[0], c[3] / c[0], c[4] / c[0]);
+
+    // depressed quartic y^4 + p y^2 + q y + r with x = y - a / 4
+    let p = b - 3.0 * a * a / 8.0;
+    let q = cc - a * b / 2.0 + a * a * a / 8.0;
+    let r = d - a * cc / 4.0 + a * a * b / 16.0 - 3.0 * a * a * a * a / 256.0;
+
+    let mut roots: Vec<f64> = vec![];
+    let mut quadratic = |b: f64, c: f64| {
+        let discriminant = b * b - 4.0 * c;
+        if discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            roots.push((-b - sqrtd) / 2.0);
+            roots.push((-b + sqrtd) / 2.0);
+        }
+    };
+
+    if q.abs() < 1e-12 {
+        // biquadratic, a quadratic in y^2
+        let discriminant = p * p - 4.0 * r;
+        if discriminant >= 0.0 {
+            let sqrtd = discriminant.sqrt();
+            for y2 in [(-p - sqrtd) / 2.0, (-p + sqrtd) / 2.0] {
+                if y2 >= 0.0 {
+                    quadratic(0.0, -y2);
+                }
+            }
+        }
+    } else {
+        // splits into (y^2 + s y + t)(y^2 - s y + u) with s^2 the largest
+        // root of the resolvent cubic, which is positive for q != 0
+        let z = largest_cubic_root(2.0 * p, p * p - 4.0 * r, -q * q).max(0.0);
+        let s = z.sqrt();
+        if s > 0.0 {
+            quadratic(s, (p + z - q / s) / 2.0);
+            quadratic(-s, (p + z + q / s) / 2.0);
+        }
+    }
+
+    let f = |x: f64| (((c[0] * x + c[1]) * x + c[2]) * x + c[3]) * x + c[4];
+    let df = |x: f64| ((4.0 * c[0] * x + 3.0 * c[1]) * x + 2.0 * c[2]) * x + c[3];
+    roots
+        .into_iter()
+        .map(|y| {
+            let mut x = y - a / 4.0;
+            for _ in 0..3 {
+                let slope = df(x);
+                if slope.abs() < 1e-12 { break }
+                x -= f(x) / slope;
+            }
+            x
+        })
+        .collect()
+}
+
+// largest real root of x^3 + a x^2 + b x + c
+fn largest_cubic_root(a: f64, b: f64, c: f64) -> f64 {
+    let q = (a * a - 3.0 * b) / 9.0;
+    let r = (2.0 * a * a * a - 9.0 * a * b + 27.0 * c) / 54.0;
+
+    match r * r < q * q * q {
+        // three real roots, the largest is the first
+        true => {
+            let theta = (r / (q * q * q).sqrt()).clamp(-1.0, 1.0).acos();
+            -2.0 * q.sqrt() * (theta / 3.0).cos() - a / 3.0
+        },
+        false => {
+            let big = -r.signum() * (r.abs() + (r * r - q * q * q).sqrt()).cbrt();
+            let small = if big != 0.0 { q / big } else { 0.0 };
+            big + small - a / 3.0
+        },
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quartic_roots() {
+        // (x - 1)(x - 2)(x - 3)(x - 4)
+        let mut roots = solve_quartic([1.0, -10.0, 35.0, -50.0, 24.0]);
+        roots.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(roots.len(), 4);
+        for (root, expected) in roots.iter().zip([1.0, 2.0, 3.0, 4.0]) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+
+        // x^4 - 5x^2 + 4, biquadratic
+        let roots = solve_quartic([1.0, 0.0, -5.0, 0.0, 4.0]);
+        assert_eq!(roots.len(), 4);
+        // x^4 + 1 has no real roots
+        assert!(solve_quartic([1.0, 0.0, 0.0, 0.0, 1.0]).is_empty());
+    }
+
+    #[test]
+    fn torus_hits() {
+        let torus = Torus::new(Vec3::zero(), Vec3::new(0.0, 1.0, 0.0), 2.0, 0.5, 0);
+        let hit = |origin: Vec3, direction: Vec3| {
+            torus.intersect(&Ray::new(origin, direction), 0.001, f64::INFINITY)
+        };
+        let left = Vec3::new(-1.0, 0.0, 0.0);
+
+        // through the middle of the ring, the outside is hit first
+        let result = hit(Vec3::new(10.0, 0.0, 0.0), left).unwrap();
+        assert!((result.t - 7.5).abs() < 1e-9);
+        assert!((result.normal - Vec3::new(1.0, 0.0, 0.0)).len() < 1e-9);
+
+        // down onto the top of the tube, and through the hole
+        let result = hit(Vec3::new(2.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).unwrap();
+        assert!((result.t - 9.5).abs() < 1e-9);
+        assert!((result.normal - Vec3::new(0.0, 1.0, 0.0)).len() < 1e-9);
+        assert!(hit(Vec3::new(0.0, 10.0, 0.0), Vec3::new(0.0, -1.0, 0.0)).is_none());
+
+        // from inside the tube out
+        let result = hit(Vec3::new(2.0, 0.0, 0.0), left).unwrap();
+        assert!((result.t - 0.5).abs() < 1e-9);
+        assert!(!result.front_face);
+
+        // grazing the top of the tube, and just above it
+        assert!(hit(Vec3::new(10.0, 0.4999, 0.0), left).is_some());
+        assert!(hit(Vec3::new(10.0, 0.5001, 0.0), left).is_none());
+
+        let bbox = torus.bbox();
+        assert!((bbox.upper - Vec3::new(2.5, 0.5, 2.5)).len() < 1e-9);
+    }
+}
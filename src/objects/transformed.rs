@@ -0,0 +1,168 @@
+use std::fmt;
+
+use crate::{ Vec3, Ray, Matrix4 };
+use crate::behaviors::{Intersect, IntersectResult};
+use crate::objects::{ Aabb, Object };
+
+
+// a matrix with the inverse and normal matrix it needs for taking rays
+// into object space and hits back out
+#[derive(Debug, Clone, Copy)]
+pub struct Transform {
+    pub matrix: Matrix4,
+    pub inverse: Matrix4,
+    // normals transform by the inverse transpose
+    pub normal: Matrix4,
+    // nothing to do for rays and hits
+    pub identity: bool,
+}
+
+
+impl Transform {
+
+    pub fn new(matrix: Matrix4) -> Self {
+        let inverse = matrix.inverse();
+        Self {
+            matrix, inverse,
+            normal: inverse.transpose(),
+            identity: matrix == Matrix4::identity(),
+        }
+    }
+
+    // the direction is not normalized, so t is the same in both spaces
+    pub fn ray(&self, ray: &Ray) -> Ray {
+        Ray::new(self.inverse * ray.origin(), self.inverse.transform_vector(ray.direction()))
+    }
+
+    // the normal still faces the ray, transforming keeps the sign of its
+    // dot product with the direction
+    pub fn result(&self, ray: &Ray, mut result: IntersectResult) -> IntersectResult {
+        result.point = ray.at(result.t);
+        result.normal = self.normal.transform_vector(result.normal).unit();
+        result.tangent = self.matrix.transform_vector(result.tangent);
+        result.bitangent = self.matrix.transform_vector(result.bitangent);
+        result
+    }
+
+    // bounds of the transformed corners
+    pub fn bbox(&self, bbox: &Aabb) -> Aabb {
+        let mut lower = Vec3::inf();
+        let mut upper = Vec3::neg_inf();
+
+        for corner in 0..8 {
+            let local = Vec3::new(
+                if corner & 1 == 0 { bbox.lower.x } else { bbox.upper.x },
+                if corner & 2 == 0 { bbox.lower.y } else { bbox.upper.y },
+                if corner & 4 == 0 { bbox.lower.z } else { bbox.upper.z },
+            );
+            let corner = self.matrix * local;
+            for i in 0..3 {
+                if corner[i] < lower[i] { lower[i] = corner[i] }
+                if corner[i] > upper[i] { upper[i] = corner[i] }
+            }
+        }
+
+        Aabb { lower, upper }
+    }
+}
+
+
+// any object moved, rotated or scaled by a matrix, spheres turn into
+// ellipsoids under non uniform scale. meshes are transformed in place
+// when divided, so only analytic shapes pay for transforming rays
+#[derive(Debug, Clone)]
+pub struct Transformed {
+    pub object: Box<Object>,
+    pub transform: Transform,
+}
+
+
+impl Transformed {
+
+    pub fn new(object: Object, matrix: Matrix4) -> Object {
+        Self { object: Box::new(object), transform: Transform::new(matrix) }.into()
+    }
+}
+
+
+impl Intersect for Transformed {
+
+    fn intersect(&self, ray: &Ray, t_min: f64, t_max: f64)
+        -> Option<IntersectResult>
+    {
+        self.object
+            .intersect(&self.transform.ray(ray), t_min, t_max)
+            .map(|result| self.transform.result(ray, result))
+    }
+
+    fn bbox(&self) -> Aabb {
+        match &*self.object {
+            // a sphere becomes an ellipsoid, its extent along each axis
+            // is the length of that row of the matrix times the radius
+            Object::Sphere(sphere) => {
+                let m = self.transform.matrix.matrix;
+                let center = self.transform.matrix * sphere.center;
+                let extent = |i: usize| sphere.radius
+                    * (m[i][0] * m[i][0] + m[i][1] * m[i][1] + m[i][2] * m[i][2]).sqrt();
+                let extent = Vec3::new(extent(0), extent(1), extent(2));
+                Aabb { lower: center - extent, upper: center + extent }
+            },
+            object => self.transform.bbox(&object.bbox()),
+        }
+    }
+
+    fn divide(&self) -> Option<Vec<Object>> {
+        match &*self.object {
+            Object::Mesh(mesh) => {
+                let mut mesh = mesh.clone();
+                mesh.transform(self.transform.matrix);
+                mesh.divide()
+            },
+            object => object.divide().map(|pieces| {
+                pieces
+                    .into_iter()
+                    .map(|piece| Transformed::new(piece, self.transform.matrix))
+                    .collect()
+            }),
+        }
+    }
+
+    fn repr(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Transformed [ object: ")?;
+        self.object.repr(f)?;
+        write!(f, ", transform: {:?} ]>", self.transform.matrix)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::objects::Sphere;
+
+    #[test]
+    fn transformed_ellipsoid() {
+        let sphere = || Sphere { center: Vec3::zero(), radius: 1.0, material: 0 }.into();
+
+        // stretched along x then turned onto y, the bbox stays tight
+        let turned = Transformed::new(
+            sphere(), Matrix4::rotate_z(90.0) * Matrix4::scale(2.0, 1.0, 1.0)
+        );
+        let bbox = turned.bbox();
+        assert!((bbox.upper - Vec3::new(1.0, 2.0, 1.0)).len() < 1e-9);
+        assert!((bbox.lower + Vec3::new(1.0, 2.0, 1.0)).len() < 1e-9);
+
+        // x^2 / 4 + y^2 = 1 hit from above at x = sqrt 2, the normal
+        // follows the gradient (x / 2, 2y)
+        let ellipsoid = Transformed::new(sphere(), Matrix4::scale(2.0, 1.0, 1.0));
+        let x = 2.0_f64.sqrt();
+        let ray = Ray::new(Vec3::new(x, 5.0, 0.0), Vec3::new(0.0, -1.0, 0.0));
+        let result = ellipsoid.intersect(&ray, 0.001, f64::INFINITY).unwrap();
+
+        let y = 0.5_f64.sqrt();
+        assert!((result.t - (5.0 - y)).abs() < 1e-9);
+        assert!((result.point - Vec3::new(x, y, 0.0)).len() < 1e-9);
+        assert!((result.normal - Vec3::new(x / 2.0, 2.0 * y, 0.0).unit()).len() < 1e-9);
+        assert!(result.front_face);
+    }
+}
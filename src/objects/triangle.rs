@@ -7,7 +7,7 @@ use crate::behaviors::{Intersect, IntersectResult};
 use crate::objects::{ Aabb, Mesh, Object };
 
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Triangle {
     pub mesh: Arc<Mesh>,
     pub offset: usize,
@@ -32,6 +32,43 @@ impl Triangle {
         ]
     }
 
+    pub fn texcoords(&self) -> [(f64, f64); 3] {
+        [
+            self.mesh.texcoords[self.mesh.texcoord_indexes[self.offset + 0]],
+            self.mesh.texcoords[self.mesh.texcoord_indexes[self.offset + 1]],
+            self.mesh.texcoords[self.mesh.texcoord_indexes[self.offset + 2]],
+        ]
+    }
+
+    // exact face derivatives, but pointing along the smooth vertex tangents
+    // when the mesh has them so normal maps do not show the faceting
+    fn tangents(&self, u: f64, v: f64, normal: Vec3) -> (Vec3, Vec3) {
+        let (dpdu, dpdv) = match self.mesh.face_tangents(self.offset) {
+            Some(derivatives) => derivatives,
+            None => return (Vec3::zero(), Vec3::zero()),
+        };
+
+        if self.mesh.tangents.is_empty() {
+            return (dpdu, dpdv)
+        }
+
+        let (t0, s0) = self.mesh.tangents[self.offset + 0];
+        let (t1, s1) = self.mesh.tangents[self.offset + 1];
+        let (t2, s2) = self.mesh.tangents[self.offset + 2];
+
+        let tangent = ((1.0 - u - v) * t0) + (u * t1) + (v * t2);
+        let tangent = tangent - normal * normal.dot(tangent);
+        if tangent.near_zero() {
+            return (dpdu, dpdv)
+        }
+
+        let sign = if s0 + s1 + s2 < 0.0 { -1.0 } else { 1.0 };
+        let tangent = tangent.unit();
+        let bitangent = sign * normal.cross(tangent);
+
+        (tangent * dpdu.len(), bitangent * dpdv.len())
+    }
+
 }
 
 
@@ -42,7 +79,7 @@ impl Intersect for Triangle {
     {
         let [v0, v1, v2] = self.vertices();
         let result = ray_triangle_intersect(
-            v0, v1, v2, false, ray, t_min, t_max
+            v0, v1, v2, self.mesh.double_sided, ray, t_min, t_max
         );
 
         if let Some((t, u, v)) = result {
@@ -56,7 +93,26 @@ impl Intersect for Triangle {
                 },
             };
 
-            return Some(IntersectResult::new(ray, t, normal, self.mesh.material))
+            // fall back to barycentric coords when mesh has no uvs
+            let (tu, tv) = match self.mesh.texcoords.len() {
+                0 => (u, v),
+                _ => {
+                    let [t0, t1, t2] = self.texcoords();
+                    (
+                        ((1.0 - u - v) * t0.0) + (u * t1.0) + (v * t2.0),
+                        ((1.0 - u - v) * t0.1) + (u * t1.1) + (v * t2.1),
+                    )
+                },
+            };
+
+            let (dpdu, dpdv) = self.tangents(u, v, normal);
+
+            return Some(
+                IntersectResult::new(ray, t, normal, self.mesh.material)
+                    .with_uv(tu, tv)
+                    .with_tangents(dpdu, dpdv)
+                    .sampled()
+            )
         }
         None
     }
@@ -94,7 +150,7 @@ impl Intersect for Triangle {
 
 pub fn ray_triangle_intersect(
     v0: Vec3, v1: Vec3, v2: Vec3, doublesided: bool,
-    ray: &Ray, _: f64, _: f64
+    ray: &Ray, t_min: f64, t_max: f64
 ) -> Option<(f64, f64, f64)> {
 
     // moller-trumbore ray-triangle intersection algo
@@ -127,8 +183,8 @@ pub fn ray_triangle_intersect(
 
     let t = v0v2.dot(qvec) * invdet;
 
-    // closer obj already found
-    // if t < t_min || t > t_max { return None }
+    // behind the ray or closer obj already found
+    if t < t_min || t > t_max { return None }
 
     unsafe { crate::INTERSECT_PASSES += 1; }
     Some((t, u, v))
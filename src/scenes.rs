@@ -5,6 +5,29 @@ mod cubes;
 mod cornell_box;
 mod airplane;
 mod temple;
+mod globes;
+mod procedural;
+mod bumpy_teapot;
+mod metals;
+mod glass;
+mod glass_teapot;
+mod material_balls;
+mod billiards;
+mod mixed;
+mod volumes;
+mod clouds;
+mod subsurface;
+mod stage;
+mod softboxes;
+mod ies_wall;
+mod teapot_forest;
+mod showcase;
+mod ellipsoids;
+mod primitives;
+mod csg;
+mod sdf;
+mod fur;
+mod subdivision;
 
 pub use spheres::spheres;
 pub use teapot_with_lights::teapot_with_lights;
@@ -12,3 +35,25 @@ pub use cubes::cubes;
 pub use cornell_box::cornell_box;
 pub use airplane::airplane;
 pub use temple::temple;
+pub use globes::globes;
+pub use procedural::procedural;
+pub use bumpy_teapot::bumpy_teapot;
+pub use metals::metals;
+pub use glass::glass;
+pub use glass_teapot::glass_teapot;
+pub use material_balls::material_balls;
+pub use billiards::billiards;
+pub use mixed::mixed;
+pub use volumes::volumes;
+pub use clouds::clouds;
+pub use subsurface::subsurface;
+pub use stage::stage;
+pub use softboxes::softboxes;
+pub use ies_wall::ies_wall;
+pub use teapot_forest::teapot_forest;
+pub use ellipsoids::ellipsoids;
+pub use primitives::primitives;
+pub use csg::csg;
+pub use sdf::sdf;
+pub use fur::fur;
+pub use subdivision::subdivision;
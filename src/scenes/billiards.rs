@@ -0,0 +1,90 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, DiffuseLight };
+
+
+#[allow(dead_code)]
+pub fn billiards(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(-2.0, 3.0, 9.0);
+    let look_at = Vec3::new(0.5, 0.4, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        // felt
+        Lambertian::new(Color::rgb(18, 92, 44)),
+        DiffuseLight::white(6.0),
+
+        // cue ball, then glossy balls, then the same paint with worn coatings
+        Plastic::new(Color::rgb(240, 236, 224), 0.0),
+        Plastic::new(Color::rgb(230, 180, 20), 0.0),
+        Plastic::new(Color::rgb(20, 40, 160), 0.0),
+        Plastic::new(Color::rgb(190, 20, 20), 0.0),
+        Plastic::new(Color::rgb(90, 20, 120), 0.0),
+        Plastic::new(Color::rgb(230, 90, 10), 0.0),
+        Plastic::new(Color::rgb(10, 10, 10), 0.0),
+        Plastic::new(Color::rgb(190, 20, 20), 0.25),
+        Plastic::new(Color::rgb(20, 40, 160), 0.5),
+    ];
+
+    let mut world: Vec<Object> = vec![
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(0.0, 10.0, 0.0),
+            radius: 3.0,
+            material: 1,
+        }.into(),
+
+        // cue ball
+        Sphere {
+            center: Vec3::new(-2.2, 0.5, 1.5),
+            radius: 0.5,
+            material: 2,
+        }.into(),
+    ];
+
+    // racked triangle of balls
+    let mut material = 3;
+    for row in 0..3 {
+        for i in 0..=row {
+            world.push(Sphere {
+                center: Vec3::new(
+                    row as f64 * 0.87,
+                    0.5,
+                    (i as f64 - row as f64 / 2.0) * 1.0,
+                ),
+                radius: 0.5,
+                material,
+            }.into());
+            material += 1;
+        }
+    }
+
+    // worn coatings off to the side
+    for i in 0..2 {
+        world.push(Sphere {
+            center: Vec3::new(1.0 + i as f64 * 1.4, 0.5, 2.4),
+            radius: 0.5,
+            material: 9 + i,
+        }.into());
+    }
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
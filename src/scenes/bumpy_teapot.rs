@@ -0,0 +1,119 @@
+use std::sync::Arc;
+
+use crate::utils::{ Vec3, Color, Camera, Image };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Metal, Dielectric, DiffuseLight };
+use crate::textures::{ ImageTexture, NoiseTexture, Pattern, ColorRamp, NormalMap };
+
+
+#[allow(dead_code)]
+pub fn bumpy_teapot(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(-6.0, 4.0, -15.0);
+    let look_at = Vec3::new(0.0, 1.4, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        25.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    let heights = ColorRamp::between(Color::rgb(0, 0, 0), Color::rgb(255, 255, 255));
+    let (bricks, bricks_normal) = bricks(1024, 1024);
+    let (bricks, bricks_normal) = (Arc::new(bricks), Arc::new(bricks_normal));
+
+    // materials
+    let materials: Vec<Material> = vec![
+        // hammered metal teapot, the obj has no uvs so the bump is solid noise
+        Metal::new(Color::rgb(204, 204, 204), 0.1)
+            .with_normal_map(NormalMap::bump(
+                NoiseTexture::new(Pattern::Worley, 5, 6.0, heights.clone()),
+                0.04,
+            )),
+
+        Lambertian::textured(ImageTexture::color_map(bricks))
+            .with_normal_map(NormalMap::tangent(
+                ImageTexture::data_map(bricks_normal), 1.0
+            )),
+
+        // frosted looking glass from small scale noise
+        Dielectric::new(1.5)
+            .with_normal_map(NormalMap::bump(
+                NoiseTexture::new(Pattern::Fbm { octaves: 4 }, 2, 30.0, heights),
+                0.002,
+            )),
+
+        DiffuseLight::white(4.0),
+    ];
+
+    let teapot = helpers::from_obj(String::from("teapot.obj"), 0);
+
+    let world: Vec<Object> = vec![
+        teapot.into(),
+
+        // brick floor
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(40.0, 0.0, -20.0),
+            1,
+        ).into(),
+
+        // glass sphere
+        Sphere {
+            center: Vec3::new(2.0, 1.0, -3.5),
+            radius: 1.0,
+            material: 2,
+        }.into(),
+
+        // light
+        Sphere {
+            center: Vec3::new(-5.0, 9.0, -5.0),
+            radius: 3.0,
+            material: 3,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
+
+
+// brick wall texture and its tangent space normal map, painted from the
+// same height field. bricks are beveled towards the mortar between them
+fn bricks(width: usize, height: usize) -> (Image, Image) {
+    let (brick_width, brick_height, mortar, bevel) = (64.0, 32.0, 4.0, 4.0);
+
+    // brick row and column, and the height at a pixel
+    let brick = |x: f64, y: f64| {
+        let row = (y / brick_height).floor();
+        let x = x + row.rem_euclid(2.0) * brick_width / 2.0;
+        let column = (x / brick_width).floor();
+        let (bx, by) = (x.rem_euclid(brick_width), y.rem_euclid(brick_height));
+        let edge = bx.min(brick_width - bx).min(by).min(brick_height - by) - mortar / 2.0;
+        (row, column, (edge / bevel).clamp(0.0, 1.0))
+    };
+
+    let color = Image::from_fn(width, height, |x, y| {
+        let (row, column, h) = brick(x as f64 + 0.5, y as f64 + 0.5);
+        if h <= 0.0 { return Vec3::new(0.55, 0.53, 0.5) }
+        let shade = ((row * 7.0 + column * 13.0).sin() * 43758.5453).rem_euclid(1.0);
+        Vec3::new(0.5 + 0.2 * shade, 0.2 + 0.08 * shade, 0.12 + 0.05 * shade)
+    });
+
+    // slopes by central differences, v runs up the image so rows flip
+    let normal = Image::from_fn(width, height, |x, y| {
+        let h = |dx: f64, dy: f64| brick(x as f64 + 0.5 + dx, y as f64 + 0.5 + dy).2;
+        let strength = 2.0;
+        let du = (h(1.0, 0.0) - h(-1.0, 0.0)) / 2.0;
+        let dv = (h(0.0, -1.0) - h(0.0, 1.0)) / 2.0;
+        let n = Vec3::new(-strength * du, -strength * dv, 1.0).unit();
+        (n + Vec3::new(1.0, 1.0, 1.0)) / 2.0
+    });
+
+    (color, normal)
+}
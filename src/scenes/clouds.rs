@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use crate::utils::{ Vec3, Color, Camera, VoxelGrid };
+use crate::objects::{ Object, Sphere, Aabb, ConstantMedium, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, DiffuseLight };
+use crate::media::{ Heterogeneous, Phase };
+
+
+#[allow(dead_code)]
+pub fn clouds(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 3.0, 14.0);
+    let look_at = Vec3::new(0.0, 2.0, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::grey(),
+        DiffuseLight::white(6.0),
+    ];
+
+    // volumes fill a box, which is both their boundary and density bounds
+    let volume = |bounds: Aabb, medium| {
+        let size = bounds.upper - bounds.lower;
+        let boundary = helpers::cuboid(
+            Vec3::new(bounds.lower.x, bounds.lower.y, bounds.upper.z),
+            size.x, size.y, size.z,
+            0,
+        );
+        ConstantMedium::new(boundary.into(), medium)
+    };
+
+    // smoke plume from a 24x36x24 voxel grid, see utils/voxels.rs for the format
+    let smoke = Arc::new(VoxelGrid::from_file("volumes/smoke.vol"));
+    let smoke_bounds = Aabb {
+        lower: Vec3::new(-4.0, 0.0, -1.5),
+        upper: Vec3::new(-1.0, 4.5, 1.5),
+    };
+
+    // procedural cumulus
+    let cloud_bounds = Aabb {
+        lower: Vec3::new(0.0, 1.0, -2.0),
+        upper: Vec3::new(4.5, 4.0, 1.0),
+    };
+
+    let world: Vec<Object> = vec![
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        volume(
+            smoke_bounds.clone(),
+            Heterogeneous::grid(
+                smoke, smoke_bounds,
+                Color::rgb(200, 200, 200), 6.0, Phase::Isotropic,
+            ),
+        ),
+
+        volume(
+            cloud_bounds.clone(),
+            Heterogeneous::cloud(
+                9, 1.2, cloud_bounds,
+                Color::rgb(250, 250, 250), 4.0, Phase::HenyeyGreenstein(0.6),
+            ),
+        ),
+
+        // light
+        Sphere {
+            center: Vec3::new(2.0, 12.0, 3.0),
+            radius: 4.0,
+            material: 1,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
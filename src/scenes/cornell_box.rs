@@ -1,12 +1,13 @@
 
 use crate::utils::{ Vec3, Color, Camera };
 use crate::objects::{ Object, helpers };
+use crate::lights::Light;
 use crate::materials::{ Material, Lambertian, Metal, DiffuseLight };
 
 
 #[allow(dead_code)]
 pub fn cornell_box(aspect_ratio: f64, dof: f64)
-    -> (Camera, Vec<Material>, Vec<Object>)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
 {
 
     // camera
@@ -90,5 +91,7 @@ pub fn cornell_box(aspect_ratio: f64, dof: f64)
     world.push(light.into());
 
 
-    (camera, materials, world)
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
 }
@@ -0,0 +1,71 @@
+use crate::utils::{ Vec3, Color, Camera, Matrix4 };
+use crate::objects::{ Object, Sphere, Cylinder, Csg, Transformed, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, Metal, Dielectric, DiffuseLight };
+
+use super::showcase::{ showcase, overhead_light };
+
+
+#[allow(dead_code)]
+pub fn csg(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    let (camera, mut world) = showcase(aspect_ratio, dof, 0);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(170, 170, 170)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Metal::gold(0.2),
+        Dielectric::new(1.5),
+        Plastic::new(Color::rgb(30, 90, 180), 0.4),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+    ];
+
+    let sphere = |center: Vec3, radius: f64, material: usize| -> Object {
+        Sphere { center, radius, material }.into()
+    };
+    let up = Vec3::new(0.0, 1.0, 0.0);
+
+    world.extend(vec![
+        // box with a spherical scoop out of its top
+        Csg::difference(
+            helpers::cuboid(Vec3::new(-5.5, 0.0, 1.0), 2.0, 2.0, 2.0, 1).into(),
+            sphere(Vec3::new(-4.5, 2.3, 0.0), 1.2, 1),
+        ),
+
+        // hollow glass ball, cut open at the front
+        Csg::difference(
+            Csg::difference(
+                sphere(Vec3::new(-1.5, 1.3, 0.0), 1.3, 3),
+                sphere(Vec3::new(-1.5, 1.3, 0.0), 1.1, 3),
+            ),
+            Cylinder::new(Vec3::new(-1.5, 1.3, 0.0), Vec3::new(0.0, 0.0, 1.0), 0.6, 2.0, 3),
+        ),
+
+        // lens from two overlapping spheres, stood on its edge
+        Transformed::new(
+            Csg::intersection(
+                sphere(Vec3::new(0.0, -1.6, 0.0), 2.0, 2),
+                sphere(Vec3::new(0.0, 1.6, 0.0), 2.0, 2),
+            ),
+            Matrix4::translate(1.5, 1.2, 0.0) * Matrix4::rotate_x(90.0),
+        ),
+
+        // three crossed rods around a ball
+        Csg::union(
+            sphere(Vec3::new(4.5, 1.2, 0.0), 0.7, 4),
+            Csg::union(
+                Cylinder::new(Vec3::new(3.3, 1.2, 0.0), Vec3::new(1.0, 0.0, 0.0), 0.25, 2.4, 4),
+                Cylinder::new(Vec3::new(4.5, 0.0, 0.0), up, 0.25, 2.4, 4),
+            ),
+        ),
+
+        overhead_light(5),
+    ]);
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
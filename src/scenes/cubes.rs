@@ -1,14 +1,16 @@
 
 use crate::utils::{ Vec3, Color, Camera };
 use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
 // use crate::materials::{ Material, Lambertian, Metal, Dielectric, DiffuseLight };
 use crate::materials::{ Material, Lambertian, DiffuseLight };
+use crate::media::{ Homogeneous, Phase };
 
 use rand::{Rng, SeedableRng};
 
 #[allow(dead_code)]
 pub fn cubes(aspect_ratio: f64, dof: f64)
-    -> (Camera, Vec<Material>, Vec<Object>)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
 {
 
     // camera
@@ -20,7 +22,11 @@ pub fn cubes(aspect_ratio: f64, dof: f64)
         // 30.0, aspect_ratio,
         80.0, aspect_ratio,
         dof, (look_from - look_at).len(),
-    );
+    )
+    // thin fog for light shafts
+    .with_medium(Homogeneous::new(
+        Color::rgb(255, 255, 255), 0.004, Phase::HenyeyGreenstein(0.3)
+    ));
 
     // materials
     let materials: Vec<Material> = vec![
@@ -130,5 +136,7 @@ pub fn cubes(aspect_ratio: f64, dof: f64)
     //     3,
     // ).into());
 
-    (camera, materials, world)
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
 }
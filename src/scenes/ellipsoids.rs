@@ -0,0 +1,67 @@
+use crate::utils::{ Vec3, Color, Camera, Matrix4 };
+use crate::objects::{ Object, Sphere, Transformed, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, Metal, Dielectric, DiffuseLight };
+
+use super::showcase::showcase;
+
+
+#[allow(dead_code)]
+pub fn ellipsoids(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    let (camera, mut world) = showcase(aspect_ratio, dof, 0);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(170, 170, 170)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Metal::gold(0.2),
+        Dielectric::new(1.5),
+        Plastic::new(Color::rgb(30, 90, 180), 0.4),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+    ];
+
+    let unit = |material: usize| -> Object {
+        Sphere { center: Vec3::zero(), radius: 1.0, material }.into()
+    };
+
+    world.extend(vec![
+        // flattened, tall and tilted ellipsoids
+        Transformed::new(
+            unit(1),
+            Matrix4::translate(-4.5, 0.6, 0.0) * Matrix4::scale(1.4, 0.6, 1.4),
+        ),
+
+        Transformed::new(
+            unit(2),
+            Matrix4::translate(-1.5, 1.6, 0.0) * Matrix4::scale(0.8, 1.6, 0.8),
+        ),
+
+        Transformed::new(
+            unit(3),
+            Matrix4::translate(1.5, 1.2, 0.5)
+                * Matrix4::rotate_z(35.0)
+                * Matrix4::scale(1.5, 0.7, 0.7),
+        ),
+
+        // a cube turned onto its corner, the mesh is transformed in place
+        Transformed::new(
+            helpers::cuboid(Vec3::new(-1.0, -1.0, 1.0), 2.0, 2.0, 2.0, 4).into(),
+            Matrix4::translate(4.5, 1.5, 0.0)
+                * Matrix4::rotate_y(45.0)
+                * Matrix4::rotate_x(35.26),
+        ),
+
+        // long light bar overhead
+        Transformed::new(
+            unit(5),
+            Matrix4::translate(0.0, 6.0, -1.0) * Matrix4::scale(5.0, 0.25, 0.25),
+        ),
+    ]);
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
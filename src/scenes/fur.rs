@@ -0,0 +1,95 @@
+use std::f64::consts::PI;
+
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, Plane, Quad, Strand, CurveKind };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Hair, DiffuseLight };
+
+
+// strands grown from a golden spiral of roots over a ball, drooping under
+// their own weight, and a tuft of grass loaded from a strand file
+#[allow(dead_code)]
+pub fn fur(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 3.0, 9.0);
+    let look_at = Vec3::new(0.0, 1.4, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(170, 170, 170)),
+        Lambertian::new(Color::rgb(60, 35, 20)),
+        Hair::new(Color::rgb(150, 90, 40), 0.15, 0.15),
+        Hair::new(Color::rgb(230, 220, 200), 0.25, 0.1),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+        Hair::new(Color::rgb(70, 120, 30), 0.2, 0.2),
+    ];
+
+    let up = Vec3::new(0.0, 1.0, 0.0);
+
+    let mut world: Vec<Object> = vec![
+        Plane::new(Vec3::zero(), up, 0),
+
+        // square light overhead, facing down
+        Quad::new(
+            Vec3::new(-2.0, 6.0, -1.0),
+            Vec3::new(4.0, 0.0, 0.0),
+            Vec3::new(0.0, 0.0, 4.0),
+            4,
+        ),
+    ];
+
+    let furry_ball = |
+        center: Vec3, radius: f64, count: usize, kind: CurveKind, material: usize
+    | {
+        let golden = PI * (3.0 - 5.0_f64.sqrt());
+        let mut strands: Vec<Object> = vec![];
+
+        for i in 0..count {
+            let y = 1.0 - 2.0 * (i as f64 + 0.5) / count as f64;
+            let ring = (1.0 - y * y).sqrt();
+            let angle = golden * i as f64;
+            let normal = Vec3::new(ring * angle.cos(), y, ring * angle.sin());
+            let root = center + radius * normal;
+
+            // longer on top, every strand a little different
+            let length = radius * (0.35 + 0.15 * ((i * 7919) % 13) as f64 / 13.0);
+            let points = (0..4)
+                .map(|k| {
+                    let s = k as f64 / 3.0;
+                    root + normal * (length * s) - up * (0.5 * length * s * s)
+                })
+                .collect();
+
+            let strand = Strand { points, width: [0.012, 0.002] };
+            strands.extend(strand.curves(kind, material));
+        }
+        strands
+    };
+
+    world.push(Sphere { center: Vec3::new(-1.3, 1.0, 0.0), radius: 0.9, material: 1 }.into());
+    world.extend(furry_ball(Vec3::new(-1.3, 1.0, 0.0), 0.9, 6000, CurveKind::Cylinder, 2));
+
+    // flat ribbons are cheaper and look the same on fine fur
+    world.push(Sphere { center: Vec3::new(1.3, 1.0, 0.0), radius: 0.9, material: 1 }.into());
+    world.extend(furry_ball(Vec3::new(1.3, 1.0, 0.0), 0.9, 6000, CurveKind::Flat, 3));
+
+    // grass in front of the balls, the file has it around the origin
+    let offset = Vec3::new(0.0, 0.0, 1.6);
+    for mut strand in Strand::from_file("strands/grass.txt") {
+        strand.points.iter_mut().for_each(|point| *point += offset);
+        world.extend(strand.curves(CurveKind::Flat, 5));
+    }
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
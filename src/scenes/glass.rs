@@ -0,0 +1,77 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Dielectric, RoughDielectric, DiffuseLight };
+use crate::textures::{ NoiseTexture, Pattern, ColorRamp };
+
+
+#[allow(dead_code)]
+pub fn glass(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 2.5, 12.0);
+    let look_at = Vec3::new(0.0, 1.0, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    let cells = ColorRamp::new(vec![
+        (0.0, Color::rgb(230, 230, 230)),
+        (0.85, Color::rgb(230, 230, 230)),
+        (1.0, Color::rgb(20, 20, 20)),
+    ]);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::grey(),
+        Lambertian::textured(NoiseTexture::new(Pattern::WorleyEdges, 1, 1.5, cells)),
+        DiffuseLight::white(6.0),
+
+        // smooth to frosted glass
+        Dielectric::new(1.5),
+        RoughDielectric::new(1.5, 0.1),
+        RoughDielectric::new(1.5, 0.3),
+        RoughDielectric::new(1.5, 0.6),
+        RoughDielectric::tinted(1.5, 0.2, Color::rgb(120, 220, 170)),
+    ];
+
+    let mut world: Vec<Object> = vec![
+        // floor
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        // patterned back wall to see the refraction
+        helpers::rect_ver(
+            Vec3::new(-20.0, 0.0, -3.0),
+            Vec3::new(20.0, 20.0, -3.0),
+            1,
+        ).into(),
+
+        // light
+        Sphere {
+            center: Vec3::new(4.0, 12.0, 8.0),
+            radius: 4.0,
+            material: 2,
+        }.into(),
+    ];
+
+    for i in 0..5 {
+        world.push(Sphere {
+            center: Vec3::new(-4.4 + i as f64 * 2.2, 1.0, 0.0),
+            radius: 1.0,
+            material: 3 + i,
+        }.into());
+    }
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,69 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Dielectric, DiffuseLight };
+
+
+#[allow(dead_code)]
+pub fn glass_teapot(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(-6.0, 4.0, -15.0);
+    let look_at = Vec3::new(0.0, 1.4, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        25.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(230, 230, 230)),
+
+        // thin walls stay light, the handle and spout get deep blue
+        Dielectric::absorbing(1.5, Color::rgb(15, 151, 204), 3.0),
+        Dielectric::absorbing(1.5, Color::rgb(218, 76, 76), 0.5),
+
+        DiffuseLight::white(4.0),
+    ];
+
+    let teapot = helpers::from_obj(String::from("teapot.obj"), 1);
+
+    let world: Vec<Object> = vec![
+        teapot.into(),
+
+        // table surface
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(40.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        // solid red glass marble
+        Sphere {
+            center: Vec3::new(2.5, 0.8, -3.5),
+            radius: 0.8,
+            material: 2,
+        }.into(),
+
+        // lights
+        Sphere {
+            center: Vec3::new(-5.0, 9.0, -5.0),
+            radius: 3.0,
+            material: 3,
+        }.into(),
+
+        Sphere {
+            center: Vec3::new(5.0, 9.0, 5.0),
+            radius: 3.0,
+            material: 3,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,104 @@
+use std::sync::Arc;
+use std::f64::consts::PI;
+
+use crate::utils::{ Vec3, Color, Camera, Image };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, DiffuseLight };
+use crate::textures::{ ImageTexture, Filter, Wrap };
+use crate::textures::noise::Perlin;
+
+
+#[allow(dead_code)]
+pub fn globes(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 2.0, 9.0);
+    let look_at = Vec3::new(0.0, 1.0, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // textures, the planet image is shared between both globes. the planet
+    // and the checker are painted in linear space, so no srgb decoding
+    let map = Arc::new(planet(512, 256));
+    let checker = Arc::new(Image::from_fn(8, 8, |x, y| match (x + y) % 2 {
+        0 => Vec3::new(0.9, 0.9, 0.9),
+        _ => Vec3::new(0.2, 0.3, 0.1),
+    }));
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::textured(ImageTexture::data_map(Arc::clone(&map))),
+        Lambertian::textured(ImageTexture::new(
+            Arc::clone(&map), Filter::Nearest, Wrap::Clamp, false
+        )),
+        Lambertian::textured(ImageTexture::new(
+            checker, Filter::Nearest, Wrap::Mirror, false
+        )),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+    ];
+
+    let world: Vec<Object> = vec![
+        Sphere {
+            center: Vec3::new(-1.2, 1.0, 0.0),
+            radius: 1.0,
+            material: 0,
+        }.into(),
+
+        Sphere {
+            center: Vec3::new(1.2, 1.0, 0.0),
+            radius: 1.0,
+            material: 1,
+        }.into(),
+
+        // floor
+        helpers::rect_hor(
+            Vec3::new(-10.0, 0.0, 10.0),
+            Vec3::new(10.0, 0.0, -10.0),
+            2,
+        ).into(),
+
+        // light
+        Sphere {
+            center: Vec3::new(-4.0, 8.0, 6.0),
+            radius: 3.0,
+            material: 3,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
+
+
+// made up planet as a latitude longitude map, continents where fractal
+// noise on the sphere is high and ice caps towards the poles
+fn planet(width: usize, height: usize) -> Image {
+    let noise = Perlin::new(7);
+    let ocean = Vec3::new(0.05, 0.15, 0.4);
+    let land = Vec3::new(0.25, 0.4, 0.12);
+    let desert = Vec3::new(0.6, 0.5, 0.3);
+    let ice = Vec3::new(0.9, 0.92, 0.95);
+
+    Image::from_fn(width, height, |x, y| {
+        let phi = 2.0 * PI * (x as f64 + 0.5) / width as f64;
+        let theta = PI * (y as f64 + 0.5) / height as f64;
+        let direction = Vec3::new(theta.sin() * phi.cos(), theta.cos(), theta.sin() * phi.sin());
+
+        let height = noise.fbm(2.0 * direction, 6);
+        let latitude = theta.cos().abs();
+        if latitude + 0.1 * height > 0.92 { return ice }
+        match height {
+            h if h < 0.05 => ocean,
+            h if h > 0.35 => desert,
+            _ => land,
+        }
+    })
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use crate::utils::{ Vec3, Color, Camera, Matrix4, IesProfile };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::{ Light, PointLight };
+use crate::materials::{ Material, Lambertian };
+
+
+// LM-63 files from ies/, made up to show off typical shapes. any type C
+// profiles from lighting manufacturers work in their place. each is hung
+// close to a white wall to show its shape
+#[allow(dead_code)]
+pub fn ies_wall(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 2.5, 14.0);
+    let look_at = Vec3::new(0.0, 2.2, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(230, 230, 230)),
+        Lambertian::new(Color::rgb(90, 90, 90)),
+    ];
+
+    let world: Vec<Object> = vec![
+        // wall and floor
+        helpers::rect_ver(
+            Vec3::new(-20.0, 0.0, -2.0),
+            Vec3::new(20.0, 10.0, -2.0),
+            0,
+        ).into(),
+
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -2.0),
+            1,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(0.0, 0.6, 1.0),
+            radius: 0.6,
+            material: 0,
+        }.into(),
+    ];
+
+    // downlights in a row along the wall. horizontal 0 of the profiles is
+    // turned towards the wall so asymmetric wall washers face it
+    let profiles = [
+        "ies/narrow_spot.ies",
+        "ies/flood.ies",
+        "ies/wall_washer.ies",
+        "ies/batwing.ies",
+    ];
+    let towards_wall = Matrix4::rotate_y(90.0);
+
+    let lights: Vec<Light> = profiles
+        .iter()
+        .enumerate()
+        .map(|(i, filename)| {
+            let x = -6.0 + 4.0 * i as f64;
+            let ies = Arc::new(IesProfile::from_file(filename));
+            PointLight::new(Vec3::new(x, 5.0, -1.4), Color::rgb(255, 236, 210), 20.0)
+                .with_profile(ies, towards_wall)
+        })
+        .collect();
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,120 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Principled, DiffuseLight };
+use crate::textures::{ NoiseTexture, Pattern, ColorRamp };
+
+
+#[allow(dead_code)]
+pub fn material_balls(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 9.0, 14.0);
+    let look_at = Vec3::new(0.0, 0.4, -1.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        34.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    let gold = Color::rgb(230, 180, 90);
+    let red = Color::rgb(180, 20, 20);
+    let blue = Color::rgb(15, 151, 204);
+    let plum = Color::rgb(90, 20, 60);
+    let pale = Color::rgb(220, 240, 250);
+
+    // materials, each row sweeps one parameter from 0 to 1 left to right.
+    // the floor is the default principled surface, for comparison
+    let mut materials: Vec<Material> = vec![
+        Principled::new(Color::rgb(128, 128, 128)),
+        DiffuseLight::white(5.0),
+    ];
+    let steps = 5;
+    for i in 0..steps {
+        let t = i as f64 / (steps - 1) as f64;
+        let silver = Color::rgb(200, 200, 200);
+        materials.extend([
+            Principled::builder(gold).metallic(t).roughness(0.3),
+            Principled::builder(silver).metallic(1.0).roughness(t),
+            Principled::builder(blue).specular(t).specular_tint(0.5).roughness(0.2),
+            Principled::builder(plum).sheen(t).roughness(0.9),
+            Principled::builder(red).clearcoat(t).clearcoat_gloss(0.9).roughness(0.7),
+            Principled::builder(pale).transmission(t).ior(1.5).roughness(0.05),
+        ].map(Material::from));
+    }
+    let rows = 6;
+
+    // a row of textured parameters at the back
+    let veins = ColorRamp::between(Color::black(), Color::rgb(255, 255, 255));
+    let textured: Vec<Material> = vec![
+        Principled::builder(gold)
+            .metallic(NoiseTexture::new(
+                Pattern::Marble { octaves: 5, distortion: 0.6 }, 2, 2.0, veins.clone()
+            ))
+            .roughness(0.25)
+            .into(),
+        Principled::builder(Color::rgb(200, 200, 200))
+            .metallic(1.0)
+            .roughness(NoiseTexture::new(Pattern::Worley, 3, 5.0, veins.clone()))
+            .into(),
+        Principled::builder(Color::rgb(10, 10, 12))
+            .roughness(0.4)
+            .emission(
+                NoiseTexture::new(
+                    Pattern::WorleyEdges, 4, 4.0,
+                    ColorRamp::new(vec![
+                        (0.0, Color::black()),
+                        (0.85, Color::black()),
+                        (1.0, Color::rgb(255, 120, 20)),
+                    ]),
+                ),
+                4.0,
+            )
+            .into(),
+    ];
+    let first_textured = materials.len();
+    materials.extend(textured);
+
+    let mut world: Vec<Object> = vec![
+        helpers::rect_hor(
+            Vec3::new(-30.0, 0.0, 30.0),
+            Vec3::new(30.0, 0.0, -30.0),
+            0,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(0.0, 16.0, 4.0),
+            radius: 6.0,
+            material: 1,
+        }.into(),
+    ];
+
+    for i in 0..steps {
+        for row in 0..rows {
+            world.push(Sphere {
+                center: Vec3::new(
+                    -4.0 + i as f64 * 2.0,
+                    0.7,
+                    -4.0 + row as f64 * 1.7,
+                ),
+                radius: 0.7,
+                material: 2 + i * rows + row,
+            }.into());
+        }
+    }
+
+    for i in 0..3 {
+        world.push(Sphere {
+            center: Vec3::new(-3.0 + i as f64 * 3.0, 1.1, -6.8),
+            radius: 1.1,
+            material: first_textured + i,
+        }.into());
+    }
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,67 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Metal, DiffuseLight };
+
+
+#[allow(dead_code)]
+pub fn metals(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 5.0, 13.0);
+    let look_at = Vec3::new(0.0, 1.2, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials, one row per metal with increasing roughness left to right
+    let roughness = [0.0, 0.2, 0.4, 0.7];
+    let mut materials: Vec<Material> = vec![
+        Lambertian::grey(),
+        DiffuseLight::white(5.0),
+    ];
+    for r in roughness {
+        materials.push(Metal::gold(r));
+        materials.push(Metal::copper(r));
+        materials.push(Metal::aluminium(r));
+        materials.push(Metal::silver(r));
+        materials.push(Metal::new(Color::rgb(15, 151, 204), r));
+    }
+
+    let mut world: Vec<Object> = vec![
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(0.0, 14.0, 6.0),
+            radius: 6.0,
+            material: 1,
+        }.into(),
+    ];
+
+    for (i, _) in roughness.iter().enumerate() {
+        for metal in 0..5 {
+            world.push(Sphere {
+                center: Vec3::new(
+                    -3.0 + i as f64 * 2.0,
+                    0.7,
+                    -4.0 + metal as f64 * 1.8,
+                ),
+                radius: 0.7,
+                material: 2 + i * 5 + metal,
+            }.into());
+        }
+    }
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
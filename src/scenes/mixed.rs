@@ -0,0 +1,84 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Metal, Dielectric, Plastic, Mix, DiffuseLight };
+use crate::textures::{ NoiseTexture, Pattern, ColorRamp };
+
+
+#[allow(dead_code)]
+pub fn mixed(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 3.5, 11.0);
+    let look_at = Vec3::new(0.0, 1.0, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // hard edged masks made from noise, black picks the first material
+    let patches = ColorRamp::new(vec![
+        (0.0, Color::black()),
+        (0.55, Color::black()),
+        (0.62, Color::rgb(255, 255, 255)),
+    ]);
+    let edges = ColorRamp::new(vec![
+        (0.0, Color::black()),
+        (0.85, Color::black()),
+        (0.9, Color::rgb(255, 255, 255)),
+    ]);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::grey(),
+        DiffuseLight::white(5.0),
+
+        // 2: worn metal, rust showing through polished steel
+        Metal::aluminium(0.15),
+        Lambertian::new(Color::rgb(120, 52, 22)),
+        Mix::new(2, 3, NoiseTexture::new(Pattern::Fbm { octaves: 6 }, 5, 2.5, patches.clone())),
+
+        // 5: dirty glass, grime patches mixed over clean glass
+        Dielectric::new(1.5),
+        Lambertian::new(Color::rgb(70, 60, 45)),
+        Mix::new(5, 6, NoiseTexture::new(Pattern::Fbm { octaves: 5 }, 6, 3.0, patches)),
+
+        // 8: emissive decal, glowing cell borders on black plastic
+        Plastic::new(Color::rgb(12, 12, 14), 0.2),
+        DiffuseLight::new(Color::rgb(40, 200, 255), 4.0),
+        Mix::new(8, 9, NoiseTexture::new(Pattern::WorleyEdges, 7, 3.0, edges)),
+
+        // 11: nested, half worn metal and half dirty glass
+        Mix::new(4, 7, 0.5),
+    ];
+
+    let mut world: Vec<Object> = vec![
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(0.0, 12.0, 4.0),
+            radius: 4.0,
+            material: 1,
+        }.into(),
+    ];
+
+    for (i, material) in [4, 7, 10, 11].into_iter().enumerate() {
+        world.push(Sphere {
+            center: Vec3::new(-3.3 + i as f64 * 2.2, 1.0, 0.0),
+            radius: 1.0,
+            material,
+        }.into());
+    }
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,51 @@
+use crate::utils::{ Vec3, Color, Camera, Matrix4 };
+use crate::objects::{ Object, Disk, Cylinder, Cone, Torus, Transformed };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, Metal, Dielectric, DiffuseLight };
+
+use super::showcase::{ showcase, overhead_light };
+
+
+#[allow(dead_code)]
+pub fn primitives(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    let (camera, mut world) = showcase(aspect_ratio, dof, 0);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(170, 170, 170)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Metal::gold(0.2),
+        Dielectric::new(1.5),
+        Plastic::new(Color::rgb(30, 90, 180), 0.4),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+        Lambertian::new(Color::rgb(60, 60, 60)),
+    ];
+
+    let up = Vec3::new(0.0, 1.0, 0.0);
+
+    world.extend(vec![
+        // a dark disk the shapes stand on
+        Disk::new(Vec3::new(0.0, 0.001, 0.0), up, 6.5, 6),
+
+        Cylinder::new(Vec3::new(-4.5, 0.0, 0.0), up, 1.0, 2.0, 1),
+        Cone::new(Vec3::new(-1.5, 0.0, 0.0), up, 1.0, 2.5, 2),
+        Torus::new(Vec3::new(1.5, 0.5, 0.5), up, 1.0, 0.5, 3),
+
+        // torus stood on its rim and squashed
+        Transformed::new(
+            Torus::new(Vec3::zero(), up, 1.0, 0.35, 4),
+            Matrix4::translate(4.5, 1.35, 0.0)
+                * Matrix4::rotate_x(90.0)
+                * Matrix4::scale(1.0, 1.0, 0.6),
+        ),
+
+        overhead_light(5),
+    ]);
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
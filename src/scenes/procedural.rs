@@ -0,0 +1,92 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, DiffuseLight };
+use crate::textures::{ NoiseTexture, Pattern, ColorRamp };
+
+
+#[allow(dead_code)]
+pub fn procedural(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 4.0, 14.0);
+    let look_at = Vec3::new(0.0, 0.8, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    let grey = ColorRamp::between(Color::rgb(20, 20, 24), Color::rgb(230, 230, 230));
+    let marble = ColorRamp::new(vec![
+        (0.0, Color::rgb(40, 44, 52)),
+        (0.4, Color::rgb(190, 190, 196)),
+        (1.0, Color::rgb(250, 250, 250)),
+    ]);
+    let wood = ColorRamp::new(vec![
+        (0.0, Color::rgb(122, 70, 32)),
+        (0.7, Color::rgb(176, 116, 60)),
+        (1.0, Color::rgb(96, 52, 22)),
+    ]);
+    let cells = ColorRamp::between(Color::rgb(15, 151, 204), Color::rgb(4, 6, 23));
+    let neon = ColorRamp::new(vec![
+        (0.0, Color::rgb(4, 6, 23)),
+        (0.8, Color::rgb(4, 6, 23)),
+        (1.0, Color::rgb(218, 6, 6)),
+    ]);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::textured(NoiseTexture::new(Pattern::Perlin, 1, 4.0, grey.clone())),
+        Lambertian::textured(NoiseTexture::new(
+            Pattern::Fbm { octaves: 6 }, 1, 3.0, grey.clone()
+        )),
+        Lambertian::textured(NoiseTexture::new(
+            Pattern::Turbulence { octaves: 6 }, 1, 3.0, grey
+        )),
+        Lambertian::textured(NoiseTexture::new(
+            Pattern::Marble { octaves: 5, distortion: 0.6 }, 2, 2.0, marble
+        )),
+        Lambertian::textured(NoiseTexture::new(
+            Pattern::Worley, 3, 4.0, cells
+        )),
+        Lambertian::textured(NoiseTexture::new(
+            Pattern::WorleyEdges, 3, 4.0, neon
+        )),
+        Lambertian::textured(NoiseTexture::new(
+            Pattern::Wood { rings: 3.0, distortion: 0.4 }, 4, 1.0, wood
+        )),
+        DiffuseLight::white(6.0),
+    ];
+
+    let mut world: Vec<Object> = vec![];
+
+    for i in 0..6 {
+        world.push(Sphere {
+            center: Vec3::new(-5.0 + i as f64 * 2.0, 0.8, 0.0),
+            radius: 0.8,
+            material: i,
+        }.into());
+    }
+
+    // wooden table
+    world.push(helpers::rect_hor(
+        Vec3::new(-20.0, 0.0, 20.0),
+        Vec3::new(20.0, 0.0, -20.0),
+        6,
+    ).into());
+
+    // light
+    world.push(Sphere {
+        center: Vec3::new(-4.0, 12.0, 8.0),
+        radius: 4.0,
+        material: 7,
+    }.into());
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
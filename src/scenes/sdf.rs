@@ -0,0 +1,71 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sdf, Distance, Aabb };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, Metal, Dielectric, DiffuseLight };
+
+use super::showcase::{ showcase, overhead_light };
+
+
+#[allow(dead_code)]
+pub fn sdf(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    let (camera, mut world) = showcase(aspect_ratio, dof, 0);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(170, 170, 170)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Metal::gold(0.2),
+        Dielectric::new(1.5),
+        Plastic::new(Color::rgb(30, 90, 180), 0.4),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+    ];
+
+    world.extend(vec![
+        // rows of studs on the floor, endless so they need a box
+        Sdf::bounded(
+            Distance::Sphere { radius: 0.15 }.repeat(Vec3::new(1.0, 0.0, 1.0)),
+            Aabb { lower: Vec3::new(-8.0, -0.15, -6.0), upper: Vec3::new(8.0, 0.15, 3.0) },
+            0,
+        ),
+
+        Sdf::new(
+            Distance::Menger { iterations: 4 }.translate(Vec3::new(-4.5, 1.0, 0.0)),
+            1,
+        ),
+
+        Sdf::new(
+            Distance::Mandelbulb { power: 8.0, iterations: 10 }
+                .translate(Vec3::new(-1.5, 1.2, 0.0)),
+            2,
+        ),
+
+        // twisted column
+        Sdf::new(
+            Distance::RoundedBox { half: Vec3::new(0.6, 1.5, 0.6), radius: 0.15 }
+                .twist(0.8)
+                .translate(Vec3::new(1.5, 1.5, 0.0)),
+            3,
+        ),
+
+        // two blobs melting into each other
+        Sdf::new(
+            Distance::Sphere { radius: 0.8 }
+                .translate(Vec3::new(4.2, 0.8, 0.0))
+                .smooth_union(
+                    Distance::Box { half: Vec3::new(0.5, 0.5, 0.5) }
+                        .translate(Vec3::new(4.9, 1.6, 0.0)),
+                    0.6,
+                ),
+            4,
+        ),
+
+        overhead_light(5),
+    ]);
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
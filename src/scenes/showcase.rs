@@ -0,0 +1,29 @@
+use crate::utils::{ Vec3, Camera };
+use crate::objects::{ Object, Plane, Quad };
+
+
+// stage shared by the scenes showing off one kind of object: a camera
+// looking at a row of them along x, standing on an endless floor
+pub fn showcase(aspect_ratio: f64, dof: f64, floor: usize) -> (Camera, Vec<Object>) {
+    let look_from = Vec3::new(0.0, 5.0, 14.0);
+    let look_at = Vec3::new(0.0, 1.2, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        35.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    (camera, vec![Plane::new(Vec3::zero(), Vec3::new(0.0, 1.0, 0.0), floor)])
+}
+
+
+// square light above the middle of the row, facing down
+pub fn overhead_light(material: usize) -> Object {
+    Quad::new(
+        Vec3::new(-2.0, 6.0, -3.0),
+        Vec3::new(4.0, 0.0, 0.0),
+        Vec3::new(0.0, 0.0, 4.0),
+        material,
+    )
+}
@@ -0,0 +1,73 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, DiffuseLight };
+
+
+#[allow(dead_code)]
+pub fn softboxes(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 5.0, 16.0);
+    let look_at = Vec3::new(0.0, 1.5, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(120, 120, 120)),
+        Plastic::new(Color::rgb(20, 80, 160), 0.3),
+        Lambertian::new(Color::rgb(200, 200, 200)),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 4.0).in_group("key"),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 4.0).two_sided().in_group("side"),
+    ];
+
+    let teapot = helpers::from_obj(String::from("teapot.obj"), 1);
+
+    let world: Vec<Object> = vec![
+        teapot.into(),
+
+        // studio floor
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        // one sided softbox on the left, the sphere behind it stays dark
+        helpers::rect_ver(
+            Vec3::new(-5.0, 0.5, 2.0),
+            Vec3::new(-5.0, 4.5, -2.0),
+            3,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(-7.5, 1.0, 0.0),
+            radius: 1.0,
+            material: 2,
+        }.into(),
+
+        // two sided panel on the right also lights the sphere behind it
+        helpers::rect_ver(
+            Vec3::new(5.0, 0.5, -2.0),
+            Vec3::new(5.0, 4.5, 2.0),
+            4,
+        ).into(),
+
+        Sphere {
+            center: Vec3::new(7.5, 1.0, 0.0),
+            radius: 1.0,
+            material: 2,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -2,12 +2,13 @@
 
 use crate::utils::{ Vec3, Color, Camera };
 use crate::objects::{ Object, Sphere };
+use crate::lights::Light;
 use crate::materials::{ Material, Lambertian, Metal, Dielectric, DiffuseLight };
 
 
 #[allow(dead_code)]
 pub fn spheres(aspect_ratio: f64, dof: f64)
-    -> (Camera, Vec<Material>, Vec<Object>)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
 {
 
     // camera
@@ -82,5 +83,7 @@ pub fn spheres(aspect_ratio: f64, dof: f64)
         }.into(),
     ];
 
-    (camera, materials, world)
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
 }
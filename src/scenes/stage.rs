@@ -0,0 +1,99 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, ConstantMedium, helpers };
+use crate::lights::{ Light, PointLight, SpotLight, DirectionalLight, Falloff };
+use crate::materials::{ Material, Lambertian, Metal, Plastic, Principled };
+use crate::media::{ Homogeneous, Phase };
+
+
+#[allow(dead_code)]
+pub fn stage(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(0.0, 4.0, 14.0);
+    let look_at = Vec3::new(0.0, 1.2, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        32.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(150, 150, 150)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Metal::gold(0.25),
+        Principled::builder(Color::rgb(40, 90, 200))
+            .roughness(0.4)
+            .clearcoat(1.0)
+            .into(),
+    ];
+
+    let teapot = helpers::from_obj(String::from("teapot.obj"), 1);
+
+    let world: Vec<Object> = vec![
+        teapot.into(),
+
+        Sphere {
+            center: Vec3::new(-4.0, 1.0, 1.0),
+            radius: 1.0,
+            material: 2,
+        }.into(),
+
+        Sphere {
+            center: Vec3::new(4.0, 1.0, 1.0),
+            radius: 1.0,
+            material: 3,
+        }.into(),
+
+        // stage floor and back wall
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        helpers::rect_ver(
+            Vec3::new(-20.0, 0.0, -6.0),
+            Vec3::new(20.0, 20.0, -6.0),
+            0,
+        ).into(),
+
+        // haze over the stage, makes the spot light cone visible
+        ConstantMedium::new(
+            helpers::cuboid(Vec3::new(-10.0, -1.0, 8.0), 20.0, 12.0, 13.5, 0).into(),
+            Homogeneous::new(Color::rgb(255, 255, 255), 0.03, Phase::HenyeyGreenstein(0.4)),
+        ),
+    ];
+
+    let lights: Vec<Light> = vec![
+        // key spot light from above
+        SpotLight::new(
+            Vec3::new(2.0, 9.0, 3.0), Vec3::new(0.0, 0.0, 0.0),
+            Color::rgb(255, 244, 229), 120.0,
+            40.0, 0.3,
+        ),
+
+        // warm fill light close to the teapot, soft rim light behind it
+        PointLight::new(
+            Vec3::new(-5.0, 3.0, 4.0),
+            Color::rgb(247, 141, 1), 6.0,
+        ).in_group("fill"),
+
+        PointLight::with_falloff(
+            Vec3::new(3.0, 5.0, -4.0),
+            Color::rgb(255, 255, 255), 0.4,
+            Falloff::Linear,
+        ).in_group("fill"),
+
+        // cold moon light from behind
+        DirectionalLight::new(
+            Vec3::new(0.3, -1.0, 0.6),
+            Color::rgb(140, 170, 255), 0.3,
+        ).in_group("moon"),
+    ];
+
+    (camera, materials, world, lights)
+}
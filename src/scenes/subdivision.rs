@@ -0,0 +1,63 @@
+use std::f64::consts::PI;
+
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Scheme, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Plastic, DiffuseLight };
+
+use super::showcase::{ showcase, overhead_light };
+
+
+#[allow(dead_code)]
+pub fn subdivision(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    let (camera, mut world) = showcase(aspect_ratio, dof, 0);
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(170, 170, 170)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Plastic::new(Color::rgb(30, 90, 180), 0.3),
+        Plastic::new(Color::rgb(220, 180, 60), 0.2),
+        DiffuseLight::new(Color::rgb(255, 244, 229), 6.0),
+    ];
+
+    // cube rounded all the way by catmull-clark
+    let cube = helpers::cuboid(Vec3::new(-5.0, 0.0, 1.0), 2.0, 2.0, 2.0, 0).vertices;
+    let cube = helpers::from_polygons(cube, vec![
+        vec![0, 1, 2, 3], vec![5, 4, 7, 6], vec![1, 5, 6, 2],
+        vec![4, 0, 3, 7], vec![3, 2, 6, 7], vec![4, 5, 1, 0],
+    ], 1)
+        .with_subdivision(Scheme::CatmullClark, 3)
+        .with_crease_angle(180.0);
+
+    // hexagonal prism, the sides meet at 60 degrees and are smoothed into
+    // a round barrel while the caps keep their hard rims
+    let ring = |y: f64| (0..6).map(move |i| {
+        let angle = i as f64 * PI / 3.0;
+        Vec3::new(-1.5 + 1.2 * angle.cos(), y, -1.2 * angle.sin())
+    });
+    let mut faces: Vec<Vec<usize>> = vec![(0..6).rev().collect(), (6..12).collect()];
+    faces.extend((0..6).map(|i| vec![i, (i + 1) % 6, (i + 1) % 6 + 6, i + 6]));
+    let prism = helpers::from_polygons(ring(0.0).chain(ring(2.4)).collect(), faces, 2)
+        .with_subdivision(Scheme::CatmullClark, 3)
+        .with_crease_angle(75.0);
+
+    // triangles refined by loop, sharp rims stay sharp
+    let mut teapot = helpers::from_obj(String::from("teapot.obj"), 3)
+        .with_subdivision(Scheme::Loop, 1);
+    teapot.translate(3.0, 0.0, 0.0);
+
+    world.extend(vec![
+        cube.into(),
+        prism.into(),
+        teapot.into(),
+        overhead_light(4),
+    ]);
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
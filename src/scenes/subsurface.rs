@@ -0,0 +1,74 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, Subsurface, DiffuseLight };
+
+
+#[allow(dead_code)]
+pub fn subsurface(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(-6.0, 4.0, -15.0);
+    let look_at = Vec3::new(0.0, 1.4, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        28.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(60, 60, 64)),
+        Subsurface::jade(),
+        Subsurface::wax(),
+        Subsurface::marble(),
+        DiffuseLight::white(4.0),
+        DiffuseLight::new(Color::rgb(247, 141, 1), 8.0),
+    ];
+
+    let teapot = helpers::from_obj(String::from("teapot.obj"), 1);
+
+    let world: Vec<Object> = vec![
+        teapot.into(),
+
+        // candle wax and marble
+        Sphere {
+            center: Vec3::new(4.0, 1.0, -3.0),
+            radius: 1.0,
+            material: 2,
+        }.into(),
+
+        Sphere {
+            center: Vec3::new(-4.0, 1.0, -3.5),
+            radius: 1.0,
+            material: 3,
+        }.into(),
+
+        // table surface
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(40.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        // key light above, warm light behind the teapot shining through
+        Sphere {
+            center: Vec3::new(-5.0, 10.0, -5.0),
+            radius: 3.0,
+            material: 4,
+        }.into(),
+
+        Sphere {
+            center: Vec3::new(2.0, 3.0, 6.0),
+            radius: 1.5,
+            material: 5,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,92 @@
+use crate::utils::{ Vec3, Color, Camera, Matrix4 };
+use crate::objects::{ Object, Sphere, Instance, Prototype, helpers };
+use crate::lights::{ Light, DirectionalLight };
+use crate::materials::{ Material, Lambertian, Plastic, Metal, DiffuseLight };
+
+use rand::{Rng, SeedableRng};
+
+
+#[allow(dead_code)]
+pub fn teapot_forest(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera
+    let look_from = Vec3::new(-14.0, 9.0, 24.0);
+    let look_at = Vec3::new(0.0, 0.0, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        40.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    );
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::new(Color::rgb(90, 110, 80)),
+        Plastic::new(Color::rgb(180, 30, 30), 0.2),
+        Plastic::new(Color::rgb(30, 90, 180), 0.4),
+        Plastic::new(Color::rgb(230, 190, 60), 0.1),
+        Metal::new(Color::rgb(204, 204, 204), 0.3),
+        Lambertian::new(Color::rgb(60, 50, 40)),
+        DiffuseLight::new(Color::rgb(255, 200, 120), 6.0),
+    ];
+
+    // every teapot and lantern shares the geometry of its prototype
+    let teapot = Prototype::new(vec![
+        helpers::from_obj(String::from("teapot.obj"), 1).into(),
+    ]);
+
+    let lantern = Prototype::new(vec![
+        helpers::cuboid(Vec3::new(-0.05, 0.0, 0.05), 0.1, 2.0, 0.1, 5).into(),
+        Sphere {
+            center: Vec3::new(0.0, 2.2, 0.0),
+            radius: 0.2,
+            material: 6,
+        }.into(),
+    ]);
+
+    let mut world: Vec<Object> = vec![
+        // ground
+        helpers::rect_hor(
+            Vec3::new(-60.0, 0.0, 60.0),
+            Vec3::new(60.0, 0.0, -60.0),
+            0,
+        ).into(),
+    ];
+
+    let mut rng = rand::rngs::SmallRng::seed_from_u64(43);
+    let n = 12;
+    let spacing = 3.5;
+    let start = -spacing * (n - 1) as f64 / 2.0;
+
+    for i in 0..n {
+        for j in 0..n {
+            let x = start + i as f64 * spacing + rng.gen_range(-0.6..0.6);
+            let z = start + j as f64 * spacing + rng.gen_range(-0.6..0.6);
+            let scale = rng.gen_range(0.25..0.45);
+
+            let transform = Matrix4::translate(x, 0.0, z)
+                * Matrix4::rotate_y(rng.gen_range(0.0..360.0))
+                * Matrix4::scale(scale, scale, scale);
+
+            world.push(Instance::with_material(&teapot, transform, rng.gen_range(1..5)));
+
+            // lanterns between some of the teapots
+            if rng.gen::<f64>() < 0.1 {
+                let offset = Matrix4::translate(x + spacing / 2.0, 0.0, z + spacing / 2.0);
+                world.push(Instance::new(&lantern, offset));
+            }
+        }
+    }
+
+    let lights: Vec<Light> = vec![
+        // dim moon light
+        DirectionalLight::new(
+            Vec3::new(0.4, -1.0, -0.3),
+            Color::rgb(140, 170, 255), 0.4,
+        ),
+    ];
+
+    (camera, materials, world, lights)
+}
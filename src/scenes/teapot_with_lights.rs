@@ -1,12 +1,13 @@
 
 use crate::utils::{ Vec3, Color, Camera };
 use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
 use crate::materials::{ Material, Lambertian, Metal, Dielectric, DiffuseLight };
 
 
 #[allow(dead_code)]
 pub fn teapot_with_lights(aspect_ratio: f64, dof: f64)
-    -> (Camera, Vec<Material>, Vec<Object>)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
 {
 
     // camera
@@ -123,5 +124,7 @@ pub fn teapot_with_lights(aspect_ratio: f64, dof: f64)
 
     ];
 
-    (camera, materials, world)
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
 }
@@ -1,12 +1,14 @@
 
 use crate::utils::{ Vec3, Color, Camera };
 use crate::objects::{ Object, Sphere, helpers };
+use crate::lights::Light;
 use crate::materials::{ Material, Lambertian, Metal, Dielectric, DiffuseLight };
+use crate::media::{ Homogeneous, Phase };
 
 
 #[allow(dead_code)]
 pub fn temple(aspect_ratio: f64, dof: f64)
-    -> (Camera, Vec<Material>, Vec<Object>)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
 {
 
     // camera
@@ -18,7 +20,11 @@ pub fn temple(aspect_ratio: f64, dof: f64)
         15.0, aspect_ratio,
         // 65.0, aspect_ratio,
         dof, (look_from - look_at).len() - 5.0,
-    );
+    )
+    // thin fog for light shafts
+    .with_medium(Homogeneous::new(
+        Color::rgb(255, 255, 255), 0.0015, Phase::HenyeyGreenstein(0.3)
+    ));
 
     // materials
     let materials: Vec<Material> = vec![
@@ -104,5 +110,7 @@ pub fn temple(aspect_ratio: f64, dof: f64)
 
     ];
 
-    (camera, materials, world)
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
 }
@@ -0,0 +1,72 @@
+use crate::utils::{ Vec3, Color, Camera };
+use crate::objects::{ Object, Sphere, ConstantMedium, helpers };
+use crate::lights::Light;
+use crate::materials::{ Material, Lambertian, DiffuseLight };
+use crate::media::{ Homogeneous, Phase };
+
+
+#[allow(dead_code)]
+pub fn volumes(aspect_ratio: f64, dof: f64)
+    -> (Camera, Vec<Material>, Vec<Object>, Vec<Light>)
+{
+
+    // camera, standing in a light haze
+    let look_from = Vec3::new(0.0, 3.0, 12.0);
+    let look_at = Vec3::new(0.0, 1.3, 0.0);
+    let camera = Camera::new(
+        look_from, look_at,
+        Vec3::new(0.0, 1.0, 0.0),
+        30.0, aspect_ratio,
+        dof, (look_from - look_at).len(),
+    )
+    .with_medium(Homogeneous::new(
+        Color::rgb(255, 255, 255), 0.02, Phase::Isotropic
+    ));
+
+    // materials
+    let materials: Vec<Material> = vec![
+        Lambertian::grey(),
+        DiffuseLight::white(6.0),
+        DiffuseLight::new(Color::rgb(247, 141, 1), 12.0),
+    ];
+
+    let world: Vec<Object> = vec![
+        helpers::rect_hor(
+            Vec3::new(-20.0, 0.0, 20.0),
+            Vec3::new(20.0, 0.0, -20.0),
+            0,
+        ).into(),
+
+        // white smoke
+        ConstantMedium::new(
+            Sphere { center: Vec3::new(-2.4, 1.2, 0.0), radius: 1.2, material: 0 }.into(),
+            Homogeneous::new(Color::rgb(240, 240, 240), 2.0, Phase::Isotropic),
+        ),
+
+        // blue tinted forward scattering block, a closed mesh boundary
+        ConstantMedium::new(
+            helpers::cuboid(Vec3::new(0.9, 0.0, 1.0), 2.0, 2.4, 2.0, 0).into(),
+            Homogeneous::new(
+                Color::rgb(190, 225, 250), 1.0, Phase::HenyeyGreenstein(0.7)
+            ),
+        ),
+
+        // lights
+        Sphere {
+            center: Vec3::new(0.0, 10.0, -4.0),
+            radius: 2.5,
+            material: 1,
+        }.into(),
+
+        // small warm light behind the smoke
+        Sphere {
+            center: Vec3::new(-2.4, 1.0, -2.5),
+            radius: 0.3,
+            material: 2,
+        }.into(),
+    ];
+
+    let lights: Vec<Light> = vec![];
+
+    (camera, materials, world, lights)
+}
@@ -0,0 +1,29 @@
+use enum_dispatch::enum_dispatch;
+
+
+pub mod solid_color;
+pub mod image_texture;
+pub mod noise;
+pub mod color_ramp;
+pub mod noise_texture;
+pub mod normal_map;
+
+pub use solid_color::SolidColor;
+pub use image_texture::{ ImageTexture, Filter, Wrap };
+pub use color_ramp::ColorRamp;
+pub use noise_texture::{ NoiseTexture, Pattern };
+pub use normal_map::NormalMap;
+
+
+// for enum dispatch
+use crate::Vec3;
+use crate::behaviors::Evaluate;
+
+
+#[enum_dispatch]
+#[derive(Debug, Clone)]
+pub enum Texture {
+    SolidColor,
+    ImageTexture,
+    NoiseTexture,
+}
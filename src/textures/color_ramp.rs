@@ -0,0 +1,52 @@
+use crate::Vec3;
+use crate::Color;
+
+
+// piecewise linear gradient over 0..1, used to color procedural patterns
+#[derive(Debug, Clone)]
+pub struct ColorRamp {
+    stops: Vec<(f64, Vec3)>,
+}
+
+
+impl ColorRamp {
+
+    pub fn new(stops: Vec<(f64, Color)>) -> Self {
+        if stops.is_empty() {
+            panic!("color ramp needs at least one stop");
+        }
+
+        let mut stops: Vec<(f64, Vec3)> = stops
+            .into_iter()
+            .map(|(t, color)| (t, color.normalize()))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self { stops }
+    }
+
+    pub fn between(start: Color, end: Color) -> Self {
+        Self::new(vec![(0.0, start), (1.0, end)])
+    }
+
+    pub fn value(&self, t: f64) -> Vec3 {
+        let first = self.stops[0];
+        let last = self.stops[self.stops.len() - 1];
+
+        if t <= first.0 { return first.1 }
+        if t >= last.0 { return last.1 }
+
+        for pair in self.stops.windows(2) {
+            let (t0, c0) = pair[0];
+            let (t1, c1) = pair[1];
+            if t <= t1 {
+                let f = match t1 - t0 > 0.0 {
+                    true => (t - t0) / (t1 - t0),
+                    false => 1.0,
+                };
+                return (1.0 - f) * c0 + f * c1
+            }
+        }
+        last.1
+    }
+}
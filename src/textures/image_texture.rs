@@ -0,0 +1,115 @@
+use std::sync::Arc;
+
+use crate::Vec3;
+use crate::utils::{ Image, color::srgb_to_linear };
+use crate::behaviors::Evaluate;
+
+use crate::textures::Texture;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Filter {
+    Nearest,
+    Bilinear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wrap {
+    Repeat,
+    Clamp,
+    Mirror,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct ImageTexture {
+    pub image: Arc<Image>,
+    pub filter: Filter,
+    pub wrap: Wrap,
+    pub srgb: bool,
+}
+
+
+impl ImageTexture {
+
+    pub fn new(image: Arc<Image>, filter: Filter, wrap: Wrap, srgb: bool) -> Texture {
+        Self { image, filter, wrap, srgb }.into()
+    }
+
+    // 8 bit color images are almost always srgb encoded
+    pub fn color_map(image: Arc<Image>) -> Texture {
+        Self::new(image, Filter::Bilinear, Wrap::Repeat, true)
+    }
+
+    // normal, roughness, height etc maps store linear data
+    pub fn data_map(image: Arc<Image>) -> Texture {
+        Self::new(image, Filter::Bilinear, Wrap::Repeat, false)
+    }
+
+    fn texel(&self, x: isize, y: isize) -> Vec3 {
+        let x = wrap(x, self.image.width, self.wrap);
+        let y = wrap(y, self.image.height, self.wrap);
+        let texel = self.image.get(x, y);
+
+        match self.srgb {
+            true => Vec3::new(
+                srgb_to_linear(texel.x),
+                srgb_to_linear(texel.y),
+                srgb_to_linear(texel.z),
+            ),
+            false => texel,
+        }
+    }
+}
+
+
+impl Evaluate for ImageTexture {
+
+    fn value(&self, u: f64, v: f64, _: Vec3) -> Vec3 {
+        if self.image.width == 0 || self.image.height == 0 {
+            return Vec3::zero()
+        }
+
+        // image rows are stored top to bottom, v goes bottom to top
+        let x = u * self.image.width as f64;
+        let y = (1.0 - v) * self.image.height as f64;
+
+        match self.filter {
+            Filter::Nearest => {
+                self.texel(x.floor() as isize, y.floor() as isize)
+            },
+            Filter::Bilinear => {
+                // texel centers are at half integer coords
+                let x = x - 0.5;
+                let y = y - 0.5;
+                let x0 = x.floor();
+                let y0 = y.floor();
+                let tx = x - x0;
+                let ty = y - y0;
+                let x0 = x0 as isize;
+                let y0 = y0 as isize;
+
+                let top = (1.0 - tx) * self.texel(x0, y0)
+                    + tx * self.texel(x0 + 1, y0);
+                let bottom = (1.0 - tx) * self.texel(x0, y0 + 1)
+                    + tx * self.texel(x0 + 1, y0 + 1);
+
+                (1.0 - ty) * top + ty * bottom
+            },
+        }
+    }
+
+}
+
+
+fn wrap(i: isize, n: usize, mode: Wrap) -> usize {
+    let n = n as isize;
+    match mode {
+        Wrap::Repeat => i.rem_euclid(n) as usize,
+        Wrap::Clamp => i.clamp(0, n - 1) as usize,
+        Wrap::Mirror => {
+            let m = i.rem_euclid(2 * n);
+            if m < n { m as usize } else { (2 * n - 1 - m) as usize }
+        },
+    }
+}
@@ -0,0 +1,233 @@
+use rand::SeedableRng;
+use rand::seq::SliceRandom;
+
+use crate::Vec3;
+use crate::utils::lerp;
+
+
+// improved perlin noise (Perlin 2002) with a seeded permutation table
+#[derive(Debug, Clone)]
+pub struct Perlin {
+    perm: Vec<usize>,
+}
+
+
+impl Perlin {
+
+    pub fn new(seed: u64) -> Self {
+        let mut rng = rand::rngs::SmallRng::seed_from_u64(seed);
+        let mut p: Vec<usize> = (0..256).collect();
+        p.shuffle(&mut rng);
+
+        // doubled so lookups of perm[perm[x] + y] never need wrapping
+        let perm = p.iter().chain(p.iter()).copied().collect();
+        Self { perm }
+    }
+
+    // roughly in -1..1 range, exactly 0 at integer lattice points
+    pub fn noise(&self, p: Vec3) -> f64 {
+        let xi = p.x.floor();
+        let yi = p.y.floor();
+        let zi = p.z.floor();
+
+        let x = p.x - xi;
+        let y = p.y - yi;
+        let z = p.z - zi;
+
+        let xi = (xi as i64 & 255) as usize;
+        let yi = (yi as i64 & 255) as usize;
+        let zi = (zi as i64 & 255) as usize;
+
+        let u = fade(x);
+        let v = fade(y);
+        let w = fade(z);
+
+        let perm = &self.perm;
+        let a = perm[xi] + yi;
+        let aa = perm[a] + zi;
+        let ab = perm[a + 1] + zi;
+        let b = perm[xi + 1] + yi;
+        let ba = perm[b] + zi;
+        let bb = perm[b + 1] + zi;
+
+        lerp(
+            lerp(
+                lerp(grad(perm[aa], x, y, z), grad(perm[ba], x - 1.0, y, z), u),
+                lerp(grad(perm[ab], x, y - 1.0, z), grad(perm[bb], x - 1.0, y - 1.0, z), u),
+                v,
+            ),
+            lerp(
+                lerp(
+                    grad(perm[aa + 1], x, y, z - 1.0),
+                    grad(perm[ba + 1], x - 1.0, y, z - 1.0),
+                    u,
+                ),
+                lerp(
+                    grad(perm[ab + 1], x, y - 1.0, z - 1.0),
+                    grad(perm[bb + 1], x - 1.0, y - 1.0, z - 1.0),
+                    u,
+                ),
+                v,
+            ),
+            w,
+        )
+    }
+
+    // fractal brownian motion, normalized back to roughly -1..1
+    pub fn fbm(&self, p: Vec3, octaves: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves.max(1) {
+            sum += amplitude * self.noise(p * frequency);
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        sum / total
+    }
+
+    // fbm of the absolute value of noise, roughly in 0..1
+    pub fn turbulence(&self, p: Vec3, octaves: usize) -> f64 {
+        let mut sum = 0.0;
+        let mut total = 0.0;
+        let mut amplitude = 1.0;
+        let mut frequency = 1.0;
+
+        for _ in 0..octaves.max(1) {
+            sum += amplitude * self.noise(p * frequency).abs();
+            total += amplitude;
+            amplitude *= 0.5;
+            frequency *= 2.0;
+        }
+        sum / total
+    }
+}
+
+
+fn fade(t: f64) -> f64 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn grad(hash: usize, x: f64, y: f64, z: f64) -> f64 {
+    // one of 12 gradient directions towards the cube edges
+    let h = hash & 15;
+    let u = if h < 8 { x } else { y };
+    let v = if h < 4 { y } else if h == 12 || h == 14 { x } else { z };
+    (if h & 1 == 0 { u } else { -u }) + (if h & 2 == 0 { v } else { -v })
+}
+
+
+
+// worley / cellular noise, one jittered feature point per unit cell
+#[derive(Debug, Clone)]
+pub struct Worley {
+    seed: u64,
+}
+
+
+impl Worley {
+
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    // distance to the closest and second closest feature points
+    pub fn noise(&self, p: Vec3) -> (f64, f64) {
+        let cx = p.x.floor() as i64;
+        let cy = p.y.floor() as i64;
+        let cz = p.z.floor() as i64;
+
+        let mut f1 = f64::INFINITY;
+        let mut f2 = f64::INFINITY;
+
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let (x, y, z) = (cx + dx, cy + dy, cz + dz);
+                    let feature = Vec3::new(x as f64, y as f64, z as f64)
+                        + self.jitter(x, y, z);
+
+                    let d = (feature - p).len();
+                    if d < f1 {
+                        f2 = f1;
+                        f1 = d;
+                    } else if d < f2 {
+                        f2 = d;
+                    }
+                }
+            }
+        }
+        (f1, f2)
+    }
+
+    fn jitter(&self, x: i64, y: i64, z: i64) -> Vec3 {
+        let mut h = self.seed;
+        h = hash(h ^ x as u64);
+        h = hash(h ^ y as u64);
+        h = hash(h ^ z as u64);
+
+        // three 21 bit chunks of the hash as floats in 0..1
+        let mask = (1 << 21) - 1;
+        let scale = 1.0 / (1u64 << 21) as f64;
+        Vec3::new(
+            (h & mask) as f64 * scale,
+            ((h >> 21) & mask) as f64 * scale,
+            ((h >> 42) & mask) as f64 * scale,
+        )
+    }
+}
+
+
+fn hash(mut x: u64) -> u64 {
+    // splitmix64 finalizer
+    x = x.wrapping_add(0x9e3779b97f4a7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+    x ^ (x >> 31)
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn perlin_zero_at_lattice() {
+        let perlin = Perlin::new(7);
+        for p in [Vec3::zero(), Vec3::new(3.0, -2.0, 17.0), Vec3::new(-40.0, 1.0, 5.0)] {
+            assert_eq!(perlin.noise(p), 0.0);
+        }
+    }
+
+    #[test]
+    fn perlin_bounded_and_seeded() {
+        let a = Perlin::new(1);
+        let b = Perlin::new(1);
+        let c = Perlin::new(2);
+
+        let mut differs = false;
+        for i in 0..1000 {
+            let p = Vec3::new(i as f64 * 0.173, i as f64 * 0.071, i as f64 * -0.119);
+            let n = a.noise(p);
+            assert!(n.abs() <= 1.0);
+            assert_eq!(n, b.noise(p));
+            differs |= n != c.noise(p);
+        }
+        assert!(differs);
+    }
+
+    #[test]
+    fn worley_ordered_distances() {
+        let worley = Worley::new(3);
+        for i in 0..100 {
+            let p = Vec3::new(i as f64 * 0.37, i as f64 * -0.21, 4.0);
+            let (f1, f2) = worley.noise(p);
+            assert!(f1 <= f2);
+            assert!(f1 < 3.0_f64.sqrt());
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{ Vec3, Matrix4 };
+use crate::behaviors::Evaluate;
+
+use crate::textures::Texture;
+use crate::textures::noise::{ Perlin, Worley };
+use crate::textures::color_ramp::ColorRamp;
+
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pattern {
+    Perlin,
+    Fbm { octaves: usize },
+    Turbulence { octaves: usize },
+    // veins along x, distorted by turbulence
+    Marble { octaves: usize, distortion: f64 },
+    // concentric rings around the y axis
+    Wood { rings: f64, distortion: f64 },
+    // distance to the closest cell point
+    Worley,
+    // distance between the closest two cell points, bright at cell borders
+    WorleyEdges,
+}
+
+
+#[derive(Debug, Clone)]
+pub struct NoiseTexture {
+    pattern: Pattern,
+    frequency: f64,
+    ramp: ColorRamp,
+    perlin: Perlin,
+    worley: Worley,
+    // world space to pattern space, identity for world space patterns
+    transform: Matrix4,
+}
+
+
+impl NoiseTexture {
+
+    pub fn new(
+        pattern: Pattern, seed: u64, frequency: f64, ramp: ColorRamp
+    ) -> Texture {
+        Self::in_object_space(pattern, seed, frequency, ramp, Matrix4::identity())
+    }
+
+    // evaluates the pattern in the space of an object placed with `placement`
+    // so the pattern sticks to the object instead of the world
+    pub fn in_object_space(
+        pattern: Pattern, seed: u64, frequency: f64, ramp: ColorRamp,
+        placement: Matrix4,
+    ) -> Texture {
+        Self {
+            pattern, frequency, ramp,
+            perlin: Perlin::new(seed),
+            worley: Worley::new(seed),
+            transform: placement.inverse(),
+        }.into()
+    }
+
+    pub fn scalar(&self, point: Vec3) -> f64 {
+        let p = (self.transform * point) * self.frequency;
+
+        let t = match self.pattern {
+            Pattern::Perlin => 0.5 * (self.perlin.noise(p) + 1.0),
+            Pattern::Fbm { octaves } => 0.5 * (self.perlin.fbm(p, octaves) + 1.0),
+            Pattern::Turbulence { octaves } => self.perlin.turbulence(p, octaves),
+            Pattern::Marble { octaves, distortion } => {
+                let turbulence = self.perlin.turbulence(p, octaves);
+                0.5 * (1.0 + (p.x + distortion * 10.0 * turbulence).sin())
+            },
+            Pattern::Wood { rings, distortion } => {
+                let r = (p.x * p.x + p.z * p.z).sqrt();
+                let r = r * rings + distortion * self.perlin.noise(p);
+                r - r.floor()
+            },
+            Pattern::Worley => self.worley.noise(p).0,
+            Pattern::WorleyEdges => {
+                let (f1, f2) = self.worley.noise(p);
+                1.0 - (f2 - f1)
+            },
+        };
+
+        t.clamp(0.0, 1.0)
+    }
+}
+
+
+impl Evaluate for NoiseTexture {
+
+    fn value(&self, _: f64, _: f64, point: Vec3) -> Vec3 {
+        self.ramp.value(self.scalar(point))
+    }
+
+}
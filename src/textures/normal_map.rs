@@ -0,0 +1,121 @@
+use crate::{ Vec3, Ray };
+use crate::behaviors::{ Evaluate, IntersectResult };
+
+use crate::textures::Texture;
+
+
+// step used for finite differences of bump textures, in uv or world units
+const DELTA: f64 = 0.0005;
+
+
+#[derive(Debug, Clone)]
+pub enum NormalMap {
+    // tangent space normals encoded as rgb, strength scales the xy tilt
+    Tangent { texture: Texture, strength: f64 },
+    // scalar height field, scale is the height in world units of a texture
+    // value of 1.0
+    Bump { texture: Texture, scale: f64 },
+}
+
+
+impl NormalMap {
+
+    pub fn tangent(texture: Texture, strength: f64) -> Self {
+        NormalMap::Tangent { texture, strength }
+    }
+
+    pub fn bump(texture: Texture, scale: f64) -> Self {
+        NormalMap::Bump { texture, scale }
+    }
+
+    // replaces the shading normal of the result with the mapped normal
+    pub fn apply(&self, ray: &Ray, result: &mut IntersectResult) {
+        let n = result.normal;
+        let (u, v, p) = (result.u, result.v, result.point);
+
+        let has_tangents = !result.tangent.near_zero() && !result.bitangent.near_zero();
+        let (t, b) = match has_tangents {
+            true => {
+                let t = (result.tangent - n * n.dot(result.tangent)).unit();
+                let b = match n.cross(t).dot(result.bitangent) < 0.0 {
+                    true => -n.cross(t),
+                    false => n.cross(t),
+                };
+                (t, b)
+            },
+            false => n.orthonormal_basis(),
+        };
+
+        let mapped = match self {
+
+            NormalMap::Tangent { texture, strength } => {
+                let c = 2.0 * texture.value(u, v, p) - Vec3::new(1.0, 1.0, 1.0);
+                t * (c.x * strength) + b * (c.y * strength) + n * c.z.max(0.0)
+            },
+
+            NormalMap::Bump { texture, scale } if has_tangents => {
+                // displaced surface p + h * n differentiated along u and v
+                let dpdu = result.tangent;
+                let dpdv = result.bitangent;
+
+                let h = texture.value(u, v, p).x;
+                let hu = texture.value(u + DELTA, v, p + DELTA * dpdu).x;
+                let hv = texture.value(u, v + DELTA, p + DELTA * dpdv).x;
+
+                let dhdu = scale * (hu - h) / DELTA;
+                let dhdv = scale * (hv - h) / DELTA;
+
+                let mapped = (dpdu + dhdu * n).cross(dpdv + dhdv * n);
+                match mapped.dot(n) < 0.0 {
+                    true => -mapped,
+                    false => mapped,
+                }
+            },
+
+            NormalMap::Bump { texture, scale } => {
+                // no uv derivatives, take the height gradient in world space
+                // which is all solid procedural textures need
+                let h = texture.value(u, v, p).x;
+                let ht = texture.value(u, v, p + DELTA * t).x;
+                let hb = texture.value(u, v, p + DELTA * b).x;
+
+                let dhdt = scale * (ht - h) / DELTA;
+                let dhdb = scale * (hb - h) / DELTA;
+
+                n - dhdt * t - dhdb * b
+            },
+        };
+
+        if mapped.near_zero() { return }
+
+        let wo = -ray.direction().unit();
+        result.normal = valid_reflection(n, wo, mapped.unit());
+    }
+}
+
+
+// a mapped normal can face away from the viewer or mirror the view direction
+// below the real surface, which turns into black pixels since every scattered
+// ray would go into the object. bend it back towards the unmapped normal
+// just enough to keep the mirror direction above the surface
+fn valid_reflection(normal: Vec3, wo: Vec3, mapped: Vec3) -> Vec3 {
+    let threshold = (0.9 * normal.dot(wo)).min(0.01);
+    let is_valid = |n: Vec3| {
+        let reflected = 2.0 * n.dot(wo) * n - wo;
+        normal.dot(reflected) >= threshold
+    };
+
+    if is_valid(mapped) { return mapped }
+
+    // bisect on the blend between both normals
+    let mut lower = 0.0;
+    let mut upper = 1.0;
+    for _ in 0..10 {
+        let mid = 0.5 * (lower + upper);
+        match is_valid(((1.0 - mid) * normal + mid * mapped).unit()) {
+            true => lower = mid,
+            false => upper = mid,
+        }
+    }
+    ((1.0 - lower) * normal + lower * mapped).unit()
+}
@@ -0,0 +1,47 @@
+
+use crate::Vec3;
+use crate::Color;
+use crate::behaviors::Evaluate;
+
+use crate::textures::Texture;
+
+
+#[derive(Debug, Clone)]
+pub struct SolidColor {
+    color: Vec3,
+}
+
+
+impl SolidColor {
+
+    pub fn new(color: Color) -> Texture {
+        Self { color: color.normalize() }.into()
+    }
+
+    pub fn from_vec3(color: Vec3) -> Texture {
+        Self { color }.into()
+    }
+}
+
+
+impl Evaluate for SolidColor {
+
+    fn value(&self, _: f64, _: f64, _: Vec3) -> Vec3 {
+        self.color
+    }
+
+}
+
+
+// lets constant parameters be passed anywhere a texture is expected
+impl From<f64> for Texture {
+    fn from(value: f64) -> Texture {
+        SolidColor::from_vec3(Vec3::new(value, value, value))
+    }
+}
+
+impl From<Color> for Texture {
+    fn from(color: Color) -> Texture {
+        SolidColor::new(color)
+    }
+}
@@ -5,12 +5,18 @@ pub mod vector;
 pub mod matrix;
 pub mod ray;
 pub mod camera;
+pub mod image;
+pub mod voxels;
+pub mod ies;
 
 pub use color::Color;
 pub use vector::Vec3;
 pub use matrix::Matrix4;
 pub use ray::Ray;
 pub use camera::Camera;
+pub use image::Image;
+pub use voxels::VoxelGrid;
+pub use ies::IesProfile;
 
 
 pub fn image_export(
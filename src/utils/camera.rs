@@ -2,6 +2,7 @@
 
 use crate::Vec3;
 use crate::Ray;
+use crate::media::Medium;
 
 
 // #[derive(Debug, Clone, Copy, PartialEq)]
@@ -15,6 +16,8 @@ pub struct Camera {
     v: Vec3,
     w: Vec3,
     lens_radius: f64,
+    // medium filling the open scene around the camera, like fog
+    pub medium: Option<Medium>,
 }
 
 
@@ -51,9 +54,15 @@ impl Camera {
             horizontal: hor,
             vertical: ver,
             lower_left: lower_left,
+            medium: None,
         }
     }
 
+    pub fn with_medium(mut self, medium: Medium) -> Camera {
+        self.medium = Some(medium);
+        self
+    }
+
     pub fn get_ray(&self, s: f64, t: f64) -> Ray {
 
         let hor = self.horizontal * s;
@@ -48,3 +48,17 @@ impl Color {
         }
     }
 }
+
+
+pub fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// relative luminance of a linear rec.709 color
+pub fn luminance(c: Vec3) -> f64 {
+    0.2126 * c.x + 0.7152 * c.y + 0.0722 * c.z
+}
@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::Path;
+
+use crate::Vec3;
+
+
+// photometric profile of a luminaire from an IES LM-63 file. only type C
+// photometry is supported, the common one for architectural lights. angles
+// are in degrees, vertical 0 points straight down from the luminaire and
+// horizontal 0 lies along its length
+//
+// in luminaire space down is -y, horizontal 0 is +x and 90 is +z
+#[derive(Debug, Clone)]
+pub struct IesProfile {
+    vertical: Vec<f64>,
+    horizontal: Vec<f64>,
+    // one row of vertical samples per horizontal angle
+    candela: Vec<f64>,
+    pub max_candela: f64,
+}
+
+
+impl IesProfile {
+
+    pub fn from_file(filename: &str) -> IesProfile {
+        let path = Path::new(filename);
+        let bytes = fs::read(path)
+            .unwrap_or_else(|_| panic!("File not found! - {}", path.display()));
+
+        IesProfile::from_bytes(&bytes)
+            .unwrap_or_else(|e| panic!("Unable to load {} - {}", path.display(), e))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<IesProfile, String> {
+        let text = String::from_utf8_lossy(bytes);
+
+        // header and keyword lines up to the TILT line, numbers after it
+        let mut lines = text.lines();
+        let tilt = lines
+            .by_ref()
+            .map(|line| line.trim())
+            .find(|line| line.starts_with("TILT="))
+            .ok_or("missing TILT= line")?;
+
+        let mut numbers = lines
+            .flat_map(|line| line.split(|c: char| c.is_ascii_whitespace() || c == ','))
+            .filter(|s| !s.is_empty())
+            .map(|s| s.parse::<f64>().map_err(|_| format!("invalid number '{}'", s)));
+        let mut next = |what: &str| -> Result<f64, String> {
+            numbers.next().unwrap_or_else(|| Err(format!("file ends before the {}", what)))
+        };
+
+        // lamp tilt only matters for lamps mounted at an angle, a tilt
+        // table in the file is skipped and tilt files are ignored
+        if &tilt[5..] == "INCLUDE" {
+            next("tilt geometry")?;
+            let pairs = next("tilt angle count")?;
+            if pairs < 0.0 || pairs.fract() != 0.0 {
+                return Err(format!("invalid tilt angle count {}", pairs));
+            }
+            for _ in 0..(2 * pairs as usize) { next("tilt table")?; }
+        }
+
+        let _lamps = next("number of lamps")?;
+        let _lumens = next("lumens per lamp")?;
+        let multiplier = next("candela multiplier")?;
+        let n_vertical = count(next("number of vertical angles")?, "vertical angles")?;
+        let n_horizontal = count(next("number of horizontal angles")?, "horizontal angles")?;
+
+        let photometric_type = next("photometric type")?;
+        if photometric_type != 1.0 {
+            return Err(format!(
+                "photometric type {} is not supported, only type C (1)", photometric_type
+            ));
+        }
+
+        // units, luminous opening, ballast factor, spare and input watts
+        for what in ["units type", "width", "length", "height", "ballast factor",
+                     "ballast lamp factor", "input watts"] {
+            next(what)?;
+        }
+
+        let mut read = |n: usize, what: &str| -> Result<Vec<f64>, String> {
+            (0..n).map(|_| next(what)).collect()
+        };
+        let vertical = read(n_vertical, "vertical angles")?;
+        let horizontal = read(n_horizontal, "horizontal angles")?;
+        let candela: Vec<f64> = read(n_vertical * n_horizontal, "candela values")?
+            .into_iter()
+            .map(|c| c * multiplier)
+            .collect();
+
+        ascending(&vertical, 0.0, 180.0, "vertical")?;
+        ascending(&horizontal, 0.0, 360.0, "horizontal")?;
+        if candela.iter().any(|c| *c < 0.0) {
+            return Err(String::from("negative candela value"));
+        }
+
+        let max_candela = candela.iter().fold(0.0_f64, |a, b| a.max(*b));
+        if max_candela <= 0.0 {
+            return Err(String::from("all candela values are zero"));
+        }
+
+        Ok(IesProfile { vertical, horizontal, candela, max_candela })
+    }
+
+    // intensity towards a direction in luminaire space, relative to the
+    // brightest direction of the profile
+    pub fn intensity(&self, direction: Vec3) -> f64 {
+        let direction = direction.unit();
+        let vertical = (-direction.y).clamp(-1.0, 1.0).acos().to_degrees();
+        let horizontal = direction.z.atan2(direction.x).to_degrees().rem_euclid(360.0);
+
+        self.candela(vertical, horizontal) / self.max_candela
+    }
+
+    // bilinear lookup, the horizontal angle folded by the symmetry the
+    // file was measured with
+    pub fn candela(&self, vertical: f64, horizontal: f64) -> f64 {
+        let last = *self.horizontal.last().unwrap();
+        let mirrored = if horizontal > 180.0 { 360.0 - horizontal } else { horizontal };
+        let horizontal = match self.horizontal.len() {
+            // rotationally symmetric
+            1 => self.horizontal[0],
+            // symmetric in each quadrant
+            _ if last == 90.0 && mirrored > 90.0 => 180.0 - mirrored,
+            _ if last == 90.0 => mirrored,
+            // symmetric about the 0-180 plane
+            _ if last == 180.0 => mirrored,
+            _ => horizontal,
+        };
+
+        // nothing is emitted outside the measured vertical range
+        let (v_first, v_last) = (self.vertical[0], *self.vertical.last().unwrap());
+        if vertical < v_first || vertical > v_last { return 0.0 }
+
+        let (v0, v1, tv) = bracket(&self.vertical, vertical);
+        let (h0, h1, th) = bracket(&self.horizontal, horizontal);
+
+        let n = self.vertical.len();
+        let at = |h: usize, v: usize| self.candela[h * n + v];
+
+        let low = at(h0, v0) * (1.0 - tv) + at(h0, v1) * tv;
+        let high = at(h1, v0) * (1.0 - tv) + at(h1, v1) * tv;
+        low * (1.0 - th) + high * th
+    }
+}
+
+
+fn count(value: f64, what: &str) -> Result<usize, String> {
+    match value >= 1.0 && value.fract() == 0.0 {
+        true => Ok(value as usize),
+        false => Err(format!("invalid number of {} {}", what, value)),
+    }
+}
+
+fn ascending(angles: &[f64], min: f64, max: f64, what: &str) -> Result<(), String> {
+    if angles.windows(2).any(|w| w[1] <= w[0]) {
+        return Err(format!("{} angles are not increasing", what));
+    }
+    if angles[0] < min || *angles.last().unwrap() > max {
+        return Err(format!("{} angles outside of {}..{} degrees", what, min, max));
+    }
+    Ok(())
+}
+
+// indexes around `x` in sorted `values` and how far between them it is,
+// clamped to the ends
+fn bracket(values: &[f64], x: f64) -> (usize, usize, f64) {
+    let i = values.partition_point(|v| *v <= x);
+    if i == 0 { return (0, 0, 0.0) }
+    if i == values.len() { return (i - 1, i - 1, 0.0) }
+    let (a, b) = (values[i - 1], values[i]);
+    (i - 1, i, (x - a) / (b - a))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUADRANT: &str = "IESNA:LM-63-2002
+[TEST] quadrant symmetric test fixture
+[MANUFAC] none
+TILT=NONE
+1 1000 2.0 3 2 1 1 0.1 0.1 0.0
+1.0 1.0 20
+0 45 90
+0 90
+100 50 0
+200 100, 0
+";
+
+    #[test]
+    fn ies_parse_and_interpolate() {
+        let profile = IesProfile::from_bytes(QUADRANT.as_bytes()).unwrap();
+        assert_eq!(profile.max_candela, 400.0);
+
+        // multiplier applied, vertical and horizontal interpolation
+        assert_eq!(profile.candela(0.0, 0.0), 200.0);
+        assert_eq!(profile.candela(22.5, 0.0), 150.0);
+        assert_eq!(profile.candela(0.0, 45.0), 300.0);
+        // quadrant symmetry folds 270 onto 90 and 135 onto 45
+        assert_eq!(profile.candela(45.0, 270.0), 200.0);
+        assert_eq!(profile.candela(0.0, 135.0), 300.0);
+        // nothing above the measured range
+        assert_eq!(profile.candela(120.0, 0.0), 0.0);
+
+        // straight down in luminaire space, and 45 degrees towards +z
+        assert_eq!(profile.intensity(Vec3::new(0.0, -1.0, 0.0)), 0.5);
+        let towards_z = Vec3::new(0.0, -1.0, 1.0);
+        assert!((profile.intensity(towards_z) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ies_tilt_include() {
+        let file = QUADRANT.replace(
+            "TILT=NONE\n", "TILT=INCLUDE\n1\n3\n0 45 90\n1.0 0.9 0.8\n"
+        );
+        assert!(IesProfile::from_bytes(file.as_bytes()).is_ok());
+    }
+
+    #[test]
+    fn ies_invalid() {
+        let invalid = |file: String| IesProfile::from_bytes(file.as_bytes()).unwrap_err();
+
+        assert!(invalid(QUADRANT.replace("TILT=NONE", "")).contains("TILT"));
+        assert!(invalid(QUADRANT.replace("200 100, 0", "200 100")).contains("candela"));
+        assert!(invalid(QUADRANT.replace("0 45 90\n", "0 90 45\n")).contains("increasing"));
+        assert!(invalid(QUADRANT.replace(" 3 2 1 1", " 3 2 2 1")).contains("type"));
+        assert!(invalid(QUADRANT.replace("100 50 0", "100 5O 0")).contains("'5O'"));
+        assert!(invalid(QUADRANT.replace(" 3 2 1 1", " 0 2 1 1")).contains("vertical"));
+    }
+}
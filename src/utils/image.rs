@@ -0,0 +1,369 @@
+use std::fs;
+use std::fmt;
+use std::path::Path;
+
+use crate::Vec3;
+
+
+// decoded image data, rows stored top to bottom. 8 bit formats are kept
+// as-is in 0..1 range, any srgb decoding is left to the texture sampling it
+#[derive(Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Vec3>,
+}
+
+
+impl Image {
+
+    #[allow(dead_code)]
+    pub fn from_file(filename: &str) -> Image {
+        let path = Path::new(filename);
+        let bytes = fs::read(path)
+            .unwrap_or_else(|_| panic!("File not found! - {}", path.display()));
+
+        let extension = path.extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        let result = match extension.as_str() {
+            "ppm" => Image::from_ppm(&bytes),
+            "png" => Image::from_png(&bytes),
+            "hdr" => Image::from_hdr(&bytes),
+            _ => Err(format!("unsupported image format '{}'", extension)),
+        };
+
+        result.unwrap_or_else(|e| panic!("Unable to load {} - {}", path.display(), e))
+    }
+
+    // painted pixel by pixel from the column and row, rows top to bottom
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> Vec3) -> Image {
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Image { width, height, pixels }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Vec3 {
+        debug_assert!(x < self.width && y < self.height);
+        self.pixels[y * self.width + x]
+    }
+
+
+    pub fn from_ppm(bytes: &[u8]) -> Result<Image, String> {
+
+        // header is 4 whitespace separated tokens: magic, width, height, max
+        // with optional comments starting with '#' until end of line
+        let mut pos = 0;
+        let mut tokens: Vec<String> = vec![];
+        while tokens.len() < 4 {
+            match bytes.get(pos) {
+                None => return Err(String::from("truncated header")),
+                Some(b'#') => {
+                    while pos < bytes.len() && bytes[pos] != b'\n' { pos += 1 }
+                },
+                Some(c) if c.is_ascii_whitespace() => pos += 1,
+                Some(_) => {
+                    let start = pos;
+                    while pos < bytes.len() && !bytes[pos].is_ascii_whitespace() {
+                        pos += 1
+                    }
+                    tokens.push(String::from_utf8_lossy(&bytes[start..pos]).into());
+                },
+            }
+        }
+        // exactly one whitespace byte separates header from binary data
+        pos += 1;
+
+        let parse = |s: &str| s.parse::<usize>()
+            .map_err(|_| format!("invalid header value '{}'", s));
+        let width = parse(&tokens[1])?;
+        let height = parse(&tokens[2])?;
+        let max = parse(&tokens[3])?;
+        if max == 0 || max > 65535 {
+            return Err(format!("invalid max value {}", max));
+        }
+
+        let n = width * height * 3;
+        let values: Vec<usize> = match tokens[0].as_str() {
+            "P3" => {
+                let txt = String::from_utf8_lossy(bytes.get(pos..).unwrap_or(&[]));
+                txt.split_ascii_whitespace()
+                    .take(n)
+                    .map(parse)
+                    .collect::<Result<Vec<usize>, String>>()?
+            },
+            "P6" => {
+                let data = bytes.get(pos..).unwrap_or(&[]);
+                match max < 256 {
+                    true => data.iter().take(n).map(|b| *b as usize).collect(),
+                    false => data.chunks_exact(2).take(n)
+                        .map(|b| ((b[0] as usize) << 8) | b[1] as usize)
+                        .collect(),
+                }
+            },
+            magic => return Err(format!("unsupported ppm type '{}'", magic)),
+        };
+
+        if values.len() < n {
+            return Err(String::from("not enough pixel data"));
+        }
+
+        let pixels = values
+            .chunks_exact(3)
+            .map(|c| Vec3::new(
+                c[0].min(max) as f64 / max as f64,
+                c[1].min(max) as f64 / max as f64,
+                c[2].min(max) as f64 / max as f64,
+            ))
+            .collect();
+
+        Ok(Image { width, height, pixels })
+    }
+
+
+    pub fn from_png(bytes: &[u8]) -> Result<Image, String> {
+        let mut decoder = png::Decoder::new(bytes);
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+        let mut buffer = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buffer).map_err(|e| e.to_string())?;
+        let data = &buffer[..info.buffer_size()];
+
+        let channels = match info.color_type {
+            png::ColorType::Grayscale => 1,
+            png::ColorType::GrayscaleAlpha => 2,
+            png::ColorType::Rgb => 3,
+            png::ColorType::Rgba => 4,
+            png::ColorType::Indexed => {
+                return Err(String::from("indexed color was not expanded"))
+            },
+        };
+
+        let pixels = data
+            .chunks_exact(channels)
+            .map(|c| match channels {
+                1 | 2 => {
+                    let g = c[0] as f64 / 255.0;
+                    Vec3::new(g, g, g)
+                },
+                _ => Vec3::new(
+                    c[0] as f64 / 255.0,
+                    c[1] as f64 / 255.0,
+                    c[2] as f64 / 255.0,
+                ),
+            })
+            .collect();
+
+        Ok(Image {
+            width: info.width as usize,
+            height: info.height as usize,
+            pixels,
+        })
+    }
+
+
+    pub fn from_hdr(bytes: &[u8]) -> Result<Image, String> {
+
+        // radiance rgbe: text header terminated by an empty line, then a
+        // resolution line, then scanlines that are either flat or new-style rle
+        let mut pos = 0;
+        let mut next_line = || -> Option<String> {
+            let start = pos;
+            while pos < bytes.len() && bytes[pos] != b'\n' { pos += 1 }
+            if pos >= bytes.len() { return None }
+            pos += 1;
+            Some(String::from_utf8_lossy(&bytes[start..pos - 1]).into())
+        };
+
+        let magic = next_line().ok_or("truncated header")?;
+        if !magic.starts_with("#?") {
+            return Err(String::from("missing radiance header"));
+        }
+
+        loop {
+            let line = next_line().ok_or("truncated header")?;
+            if line.is_empty() { break }
+            if line.starts_with("FORMAT=") && line != "FORMAT=32-bit_rle_rgbe" {
+                return Err(format!("unsupported format '{}'", &line[7..]));
+            }
+        }
+
+        let resolution = next_line().ok_or("missing resolution")?;
+        let opts: Vec<&str> = resolution.split_ascii_whitespace().collect();
+        if opts.len() != 4 || opts[0] != "-Y" || opts[2] != "+X" {
+            return Err(format!("unsupported orientation '{}'", resolution));
+        }
+        let parse = |s: &str| s.parse::<usize>()
+            .map_err(|_| format!("invalid resolution '{}'", resolution));
+        let height = parse(opts[1])?;
+        let width = parse(opts[3])?;
+
+        let mut data = bytes.get(pos..).unwrap_or(&[]).iter().copied();
+        let mut next = || data.next().ok_or_else(|| String::from("truncated pixel data"));
+
+        let mut pixels = Vec::with_capacity(width * height);
+        let mut scanline = vec![[0u8; 4]; width];
+
+        for _ in 0..height {
+            let header = [next()?, next()?, next()?, next()?];
+            let is_rle = (8..0x8000).contains(&width)
+                && header[0] == 2 && header[1] == 2 && header[2] & 0x80 == 0;
+
+            if is_rle {
+                if ((header[2] as usize) << 8 | header[3] as usize) != width {
+                    return Err(String::from("scanline width mismatch"));
+                }
+
+                // each of the 4 channels is run length encoded separately
+                for channel in 0..4 {
+                    let mut x = 0;
+                    while x < width {
+                        let count = next()? as usize;
+                        if count > 128 {
+                            let value = next()?;
+                            let count = count - 128;
+                            if x + count > width {
+                                return Err(String::from("bad scanline data"));
+                            }
+                            for texel in scanline[x..(x + count)].iter_mut() {
+                                texel[channel] = value;
+                            }
+                            x += count;
+                        } else {
+                            if count == 0 || x + count > width {
+                                return Err(String::from("bad scanline data"));
+                            }
+                            for texel in scanline[x..(x + count)].iter_mut() {
+                                texel[channel] = next()?;
+                            }
+                            x += count;
+                        }
+                    }
+                }
+            } else {
+                scanline[0] = header;
+                for texel in scanline.iter_mut().skip(1) {
+                    *texel = [next()?, next()?, next()?, next()?];
+                }
+            }
+
+            for [r, g, b, e] in scanline.iter() {
+                pixels.push(match e {
+                    0 => Vec3::zero(),
+                    e => {
+                        let f = 2.0_f64.powi(*e as i32 - (128 + 8));
+                        Vec3::new(*r as f64 * f, *g as f64 * f, *b as f64 * f)
+                    },
+                });
+            }
+        }
+
+        Ok(Image { width, height, pixels })
+    }
+}
+
+
+impl fmt::Debug for Image {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<Image [{}x{}]>", self.width, self.height)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use crate::behaviors::Evaluate;
+    use crate::textures::{ ImageTexture, Filter, Wrap };
+
+    #[test]
+    fn ppm_ascii() {
+        let bytes = b"P3\n# comment\n2 1\n255\n255 0 0  0 51 255\n";
+        let image = Image::from_ppm(bytes).unwrap();
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.get(0, 0), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(image.get(1, 0), Vec3::new(0.0, 0.2, 1.0));
+    }
+
+    #[test]
+    fn ppm_binary() {
+        let mut bytes = b"P6 1 2 255\n".to_vec();
+        bytes.extend([0, 255, 0, 255, 255, 255]);
+        let image = Image::from_ppm(&bytes).unwrap();
+        assert_eq!(image.get(0, 0), Vec3::new(0.0, 1.0, 0.0));
+        assert_eq!(image.get(0, 1), Vec3::new(1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn image_from_fn() {
+        let image = Image::from_fn(3, 2, |x, y| Vec3::new(x as f64, y as f64, 0.0));
+        assert_eq!(image.pixels.len(), 6);
+        assert_eq!(image.get(2, 0), Vec3::new(2.0, 0.0, 0.0));
+        assert_eq!(image.get(1, 1), Vec3::new(1.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn ppm_truncated() {
+        assert!(Image::from_ppm(b"P3\n2 2\n255\n0 0 0").is_err());
+        assert!(Image::from_ppm(b"P5\n1 1\n255\n0").is_err());
+    }
+
+    // encoded in memory, decoded from a file like scenes load textures
+    #[test]
+    fn png_round_trip() {
+        let mut bytes: Vec<u8> = vec![];
+        let mut encoder = png::Encoder::new(&mut bytes, 2, 1);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[255, 0, 0, 0, 51, 255]).unwrap();
+        writer.finish().unwrap();
+
+        let path = std::env::temp_dir().join("raytracer_png_round_trip.png");
+        fs::write(&path, &bytes).unwrap();
+        let image = Image::from_file(path.to_str().unwrap());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.get(0, 0), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(image.get(1, 0), Vec3::new(0.0, 0.2, 1.0));
+
+        // a texel past either side of the image, for every wrap mode
+        let red = Vec3::new(1.0, 0.0, 0.0);
+        let blue = Vec3::new(0.0, 0.2, 1.0);
+        let image = Arc::new(image);
+        for (wrap, left, right) in [
+            (Wrap::Repeat, blue, red),
+            (Wrap::Clamp, red, blue),
+            (Wrap::Mirror, red, blue),
+        ] {
+            let texture = ImageTexture::new(Arc::clone(&image), Filter::Nearest, wrap, false);
+            assert_eq!(texture.value(-0.25, 0.5, Vec3::zero()), left);
+            assert_eq!(texture.value(1.25, 0.5, Vec3::zero()), right);
+        }
+    }
+
+    #[test]
+    fn hdr_flat_and_rle() {
+        let mut bytes = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 8\n".to_vec();
+
+        // flat scanline, rgbe(128, 64, 0, 129) = (1.0, 0.5, 0.0)
+        for _ in 0..8 { bytes.extend([128, 64, 0, 129]); }
+
+        // rle scanline, every channel a single run of 8
+        bytes.extend([2, 2, 0, 8]);
+        for value in [128, 128, 128, 130] { bytes.extend([128 + 8, value]); }
+
+        let image = Image::from_hdr(&bytes).unwrap();
+        assert_eq!((image.width, image.height), (8, 2));
+        assert_eq!(image.get(3, 0), Vec3::new(1.0, 0.5, 0.0));
+        assert_eq!(image.get(7, 1), Vec3::new(2.0, 2.0, 2.0));
+    }
+}
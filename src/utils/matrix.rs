@@ -17,6 +17,14 @@ impl Matrix4 {
         Self { matrix: [m[0], m[1], m[2], [0.0, 0.0, 0.0, 1.0]] }
     }
 
+    pub fn identity() -> Self {
+        Self::new([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+        ])
+    }
+
     pub fn translate(tx: f64, ty: f64, tz: f64) -> Self {
         Self::new([
             [1.0, 0.0, 0.0, tx],
@@ -132,6 +140,18 @@ impl Matrix4 {
     }
 
 
+    // applies only the linear part, for directions instead of points
+    pub fn transform_vector(&self, v: Vec3) -> Vec3 {
+        let mut result = Vec3::zero();
+        for i in 0..3 {
+            result[i] = (self.matrix[i][0] * v.x)
+                      + (self.matrix[i][1] * v.y)
+                      + (self.matrix[i][2] * v.z);
+        }
+        result
+    }
+
+
     pub fn transpose(&self) -> Self {
         let mut transpose = [[0.0; 4]; 4];
         for i in 0..4 {
@@ -158,6 +178,20 @@ impl Mul<Vec3> for Matrix4 {
     }
 }
 
+// applies the right matrix first, then the left one
+impl Mul<Matrix4> for Matrix4 {
+    type Output = Matrix4;
+    fn mul(self, other: Matrix4) -> Matrix4 {
+        let mut result = [[0.0; 4]; 4];
+        for (i, row) in result.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.matrix[i][k] * other.matrix[k][j]).sum();
+            }
+        }
+        Self { matrix: result }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -214,4 +248,24 @@ mod tests {
             [3.0, 8.0, 9.0, 1.0],
         ]);
     }
+
+    #[test]
+    fn matrix_multiply() {
+        let matrix = Matrix4::translate(1.0, 2.0, 3.0) * Matrix4::scale(2.0, 2.0, 2.0);
+        assert_eq!(matrix * Vec3::new(1.0, 1.0, 1.0), Vec3::new(3.0, 4.0, 5.0));
+
+        let matrix = Matrix4::new([
+            [2.0, 4.0, 1.0, 3.0],
+            [3.0, 5.0, 7.0, 8.0],
+            [7.0, 8.0, 9.0, 9.0],
+        ]);
+        assert_eq!(matrix * Matrix4::identity(), matrix);
+        let product = matrix * matrix.inverse();
+        for i in 0..4 {
+            for j in 0..4 {
+                let expected = if i == j { 1.0 } else { 0.0 };
+                assert!((product.matrix[i][j] - expected).abs() < 1e-12);
+            }
+        }
+    }
 }
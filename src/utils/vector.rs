@@ -64,6 +64,16 @@ impl Vec3 {
         self.x.abs() < e && self.y.abs() < e && self.z.abs() < e
     }
 
+    // two unit vectors perpendicular to self and each other, self must be unit
+    pub fn orthonormal_basis(&self) -> (Vec3, Vec3) {
+        let a = match self.x.abs() > 0.9 {
+            true => Vec3::new(0.0, 1.0, 0.0),
+            false => Vec3::new(1.0, 0.0, 0.0),
+        };
+        let t = self.cross(a).unit();
+        (t, self.cross(t))
+    }
+
     // --------------------------------------------------------
 
     pub fn reflect(vector: Vec3, normal: Vec3) -> Vec3 {
@@ -0,0 +1,144 @@
+use std::fs;
+use std::fmt;
+use std::path::Path;
+
+use crate::Vec3;
+
+
+// dense grid of density values, read from a minimal raw format:
+//
+//     VOXELS\n
+//     <nx> <ny> <nz>\n
+//     nx * ny * nz little endian f32 values, x fastest then y then z
+//
+// easy to write from any script, e.g. numpy's `array.astype('<f4').tofile()`
+// on an array indexed [z][y][x] after writing the two header lines
+#[derive(Clone)]
+pub struct VoxelGrid {
+    pub nx: usize,
+    pub ny: usize,
+    pub nz: usize,
+    pub data: Vec<f32>,
+}
+
+
+impl VoxelGrid {
+
+    pub fn from_file(filename: &str) -> VoxelGrid {
+        let path = Path::new(filename);
+        let bytes = fs::read(path)
+            .unwrap_or_else(|_| panic!("File not found! - {}", path.display()));
+
+        VoxelGrid::from_bytes(&bytes)
+            .unwrap_or_else(|e| panic!("Unable to load {} - {}", path.display(), e))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<VoxelGrid, String> {
+        let mut lines = bytes.splitn(3, |b| *b == b'\n');
+
+        let magic = lines.next().unwrap_or(&[]);
+        if magic != b"VOXELS" {
+            return Err(String::from("missing VOXELS header"));
+        }
+
+        let dims = lines.next().ok_or("truncated header")?;
+        let dims = String::from_utf8_lossy(dims);
+        let dims: Vec<usize> = dims
+            .split_ascii_whitespace()
+            .map(|s| s.parse::<usize>().map_err(|_| format!("invalid size '{}'", s)))
+            .collect::<Result<Vec<usize>, String>>()?;
+        if dims.len() != 3 || dims.contains(&0) {
+            return Err(format!("expected 3 non zero sizes, got {:?}", dims));
+        }
+        let (nx, ny, nz) = (dims[0], dims[1], dims[2]);
+
+        let data = lines.next().unwrap_or(&[]);
+        let n = nx * ny * nz;
+        if data.len() < n * 4 {
+            return Err(format!(
+                "not enough voxel data, expected {} bytes got {}", n * 4, data.len()
+            ));
+        }
+
+        let data = data
+            .chunks_exact(4)
+            .take(n)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+
+        Ok(VoxelGrid { nx, ny, nz, data })
+    }
+
+    pub fn get(&self, x: usize, y: usize, z: usize) -> f64 {
+        debug_assert!(x < self.nx && y < self.ny && z < self.nz);
+        self.data[(z * self.ny + y) * self.nx + x] as f64
+    }
+
+    pub fn max(&self) -> f64 {
+        self.data.iter().fold(0.0_f32, |a, b| a.max(*b)) as f64
+    }
+
+    // trilinear lookup with p in 0..1 over the whole grid, voxel values sit
+    // at voxel centers and are clamped at the borders
+    pub fn sample(&self, p: Vec3) -> f64 {
+        let coord = |t: f64, n: usize| {
+            let x = (t * n as f64 - 0.5).clamp(0.0, (n - 1) as f64);
+            let i = (x.floor() as usize).min(n.saturating_sub(2));
+            (i, (i + 1).min(n - 1), x - i as f64)
+        };
+        let (x0, x1, tx) = coord(p.x, self.nx);
+        let (y0, y1, ty) = coord(p.y, self.ny);
+        let (z0, z1, tz) = coord(p.z, self.nz);
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let plane = |z: usize| lerp(
+            lerp(self.get(x0, y0, z), self.get(x1, y0, z), tx),
+            lerp(self.get(x0, y1, z), self.get(x1, y1, z), tx),
+            ty,
+        );
+        lerp(plane(z0), plane(z1), tz)
+    }
+}
+
+
+impl fmt::Debug for VoxelGrid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "<VoxelGrid [{}x{}x{}]>", self.nx, self.ny, self.nz)
+    }
+}
+
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_bytes(header: &str, values: &[f32]) -> Vec<u8> {
+        let mut bytes = header.as_bytes().to_vec();
+        for v in values { bytes.extend(v.to_le_bytes()); }
+        bytes
+    }
+
+    #[test]
+    fn voxels_parse_and_interpolate() {
+        let values = [0.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 1.0];
+        let grid = VoxelGrid::from_bytes(&grid_bytes("VOXELS\n2 2 2\n", &values)).unwrap();
+        assert_eq!((grid.nx, grid.ny, grid.nz), (2, 2, 2));
+        assert_eq!(grid.get(1, 0, 1), 1.0);
+        assert_eq!(grid.max(), 1.0);
+
+        // halfway between the voxel centers along x
+        assert!((grid.sample(Vec3::new(0.5, 0.3, 0.9)) - 0.5).abs() < 1e-9);
+        // clamped at the border
+        assert_eq!(grid.sample(Vec3::new(0.0, 0.0, 0.0)), 0.0);
+        assert_eq!(grid.sample(Vec3::new(1.0, 0.5, 0.5)), 1.0);
+    }
+
+    #[test]
+    fn voxels_invalid() {
+        assert!(VoxelGrid::from_bytes(&grid_bytes("VOXELS\n2 2 2\n", &[0.0; 7])).is_err());
+        assert!(VoxelGrid::from_bytes(&grid_bytes("VOXELS\n2 0 2\n", &[])).is_err());
+        assert!(VoxelGrid::from_bytes(&grid_bytes("VOXEL\n1 1 1\n", &[1.0])).is_err());
+        assert!(VoxelGrid::from_bytes(b"VOXELS\n1 1").is_err());
+    }
+}